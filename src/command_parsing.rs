@@ -1,6 +1,13 @@
-use crate::scrabble_base_types::{Direction, Placement, PlayerID, ScrabbleLetter};
+use crate::scrabble_base_types::{
+    Direction, Placement, PlayerID, Position, ScrabbleLetter, SymbolTable, MAX_COORDINATE,
+    MAX_PLACEMENT_LENGTH,
+};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
+// separates multiple commands given on a single line
+pub const COMMAND_SEPARATOR: &str = " && ";
+
 #[derive(Debug, Clone)]
 pub enum CommandParseError {
     UnknownCommand {
@@ -15,6 +22,18 @@ pub enum CommandParseError {
     InvalidLetters {
         letters: String,
     },
+    InvalidNumber {
+        number: String,
+    },
+    InvalidToggle {
+        value: String,
+    },
+    InvalidDirection {
+        direction: String,
+    },
+    InvalidPosition {
+        position: String,
+    },
     InvalidArgumentCount {
         command: String,
         expected: usize,
@@ -39,6 +58,20 @@ impl std::fmt::Display for CommandParseError {
             CommandParseError::InvalidLetters { letters } => {
                 write!(formatter, "Error: '{}' contains invalid letters!", letters)
             }
+            CommandParseError::InvalidNumber { number } => {
+                write!(formatter, "Error: '{}' is not a valid number!", number)
+            }
+            CommandParseError::InvalidToggle { value } => {
+                write!(formatter, "Error: '{}' is neither 'on' nor 'off'!", value)
+            }
+            CommandParseError::InvalidDirection { direction } => write!(
+                formatter,
+                "Error: '{}' is not a valid direction, use H or V!",
+                direction
+            ),
+            CommandParseError::InvalidPosition { position } => {
+                write!(formatter, "Error: '{}' is not a valid position!", position)
+            }
             CommandParseError::InvalidArgumentCount {
                 command,
                 expected,
@@ -60,25 +93,107 @@ impl std::error::Error for CommandParseError {}
 pub enum Command {
     Quit,
     Print,
+    PrintCropped,
     Score(PlayerID),
     Bag(PlayerID),
     Place(Placement),
+    Undo,
+    Config,
+    Drops(ScrabbleLetter),
+    Deal(usize),
+    Territory,
+    History,
+    Verbose(bool),
+    Lock(Position),
+    Gap,
+    CanMove,
+    ExportCsv(String),
+    ImportCsv(String),
+    ExportMoves(String),
+    ImportMoves(String),
+    Potential,
+    Stats,
+    Seed(u64),
+    Goal(i64),
+    Explain(ScrabbleLetter),
+    Pass,
+    Census,
+    Note(Position, String),
+    Notes,
 }
 
 impl FromStr for Command {
     type Err = CommandParseError;
 
     fn from_str(command_str: &str) -> Result<Self, Self::Err> {
+        Command::parse_with(command_str, &SymbolTable::default())
+    }
+}
+
+impl Command {
+    // letters are read through the symbol table of the player entering the command
+    pub fn parse_with(
+        command_str: &str,
+        symbols: &SymbolTable,
+    ) -> Result<Command, CommandParseError> {
         let arg_count = command_str.split(' ').collect::<Vec<&str>>().len() - 1;
 
         match &command_str.split(' ').collect::<Vec<&str>>()[..] {
             ["quit"] => Ok(Command::Quit),
             ["print"] => Ok(Command::Print),
-            ["score", player_id] => player_id_from_str(player_id).map(|id| Command::Score(id)),
-            ["bag", player_id] => player_id_from_str(player_id).map(|id| Command::Bag(id)),
-            ["place", placement] => {
-                placement_from_str(placement).map(|placement| Command::Place(placement))
+            ["print", "cropped"] => Ok(Command::PrintCropped),
+            ["undo"] => Ok(Command::Undo),
+            ["pass"] => Ok(Command::Pass),
+            ["config"] => Ok(Command::Config),
+            ["territory"] => Ok(Command::Territory),
+            ["history"] => Ok(Command::History),
+            ["gap"] => Ok(Command::Gap),
+            ["canmove"] => Ok(Command::CanMove),
+            ["potential"] => Ok(Command::Potential),
+            ["stats"] => Ok(Command::Stats),
+            ["census"] => Ok(Command::Census),
+            ["notes"] => Ok(Command::Notes),
+            ["verbose", "on"] => Ok(Command::Verbose(true)),
+            ["verbose", "off"] => Ok(Command::Verbose(false)),
+            ["verbose", value] => Err(CommandParseError::InvalidToggle {
+                value: value.to_string(),
+            }),
+            ["explain", letter] => letter_from_str(letter, symbols).map(Command::Explain),
+            ["drops", letter] => letter_from_str(letter, symbols).map(Command::Drops),
+            ["deal", count] => {
+                count
+                    .parse()
+                    .map(Command::Deal)
+                    .map_err(|_| CommandParseError::InvalidNumber {
+                        number: count.to_string(),
+                    })
             }
+            ["seed", seed] => {
+                seed.parse()
+                    .map(Command::Seed)
+                    .map_err(|_| CommandParseError::InvalidNumber {
+                        number: seed.to_string(),
+                    })
+            }
+            ["goal", goal] => {
+                goal.parse()
+                    .map(Command::Goal)
+                    .map_err(|_| CommandParseError::InvalidNumber {
+                        number: goal.to_string(),
+                    })
+            }
+            ["score", player_id] => player_id_from_str(player_id).map(Command::Score),
+            ["bag", player_id] => player_id_from_str(player_id).map(Command::Bag),
+            ["lock", position] => position_from_str(position).map(Command::Lock),
+            ["note", x, y, text @ ..] if !text.is_empty() => {
+                position_from_str(&format!("{};{}", x, y))
+                    .map(|position| Command::Note(position, text.join(" ")))
+            }
+            ["exportcsv", path] => Ok(Command::ExportCsv(path.to_string())),
+            ["importcsv", path] => Ok(Command::ImportCsv(path.to_string())),
+            ["exportmoves", path] => Ok(Command::ExportMoves(path.to_string())),
+            ["importmoves", path] => Ok(Command::ImportMoves(path.to_string())),
+            ["place", placement] => placement_from_str(placement, symbols).map(Command::Place),
 
             ["quit", ..] => Err(CommandParseError::InvalidArgumentCount {
                 command: "quit".to_string(),
@@ -90,6 +205,96 @@ impl FromStr for Command {
                 expected: 0,
                 received: arg_count,
             }),
+            ["undo", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "undo".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["pass", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "pass".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["config", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "config".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["explain", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "explain".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["drops", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "drops".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["deal", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "deal".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["seed", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "seed".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["goal", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "goal".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["territory", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "territory".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["history", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "history".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["gap", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "gap".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["canmove", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "canmove".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["potential", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "potential".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["notes", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "notes".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["note", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "note".to_string(),
+                expected: 3,
+                received: arg_count,
+            }),
+            ["census", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "census".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["stats", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "stats".to_string(),
+                expected: 0,
+                received: arg_count,
+            }),
+            ["verbose", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "verbose".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
             ["score", ..] => Err(CommandParseError::InvalidArgumentCount {
                 command: "score".to_string(),
                 expected: 1,
@@ -100,6 +305,31 @@ impl FromStr for Command {
                 expected: 1,
                 received: arg_count,
             }),
+            ["lock", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "lock".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["exportmoves", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "exportmoves".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["importmoves", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "importmoves".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["exportcsv", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "exportcsv".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
+            ["importcsv", ..] => Err(CommandParseError::InvalidArgumentCount {
+                command: "importcsv".to_string(),
+                expected: 1,
+                received: arg_count,
+            }),
             ["place", ..] => Err(CommandParseError::InvalidArgumentCount {
                 command: "place".to_string(),
                 expected: 1,
@@ -113,6 +343,30 @@ impl FromStr for Command {
     }
 }
 
+// parses every command of a script without executing it, returning the errors with their line numbers.
+// whose turn a line is on isn't known without playing it, so the glyphs of any player are accepted
+pub fn check_script(
+    script: &str,
+    symbol_tables: &BTreeMap<PlayerID, SymbolTable>,
+) -> Vec<(usize, CommandParseError)> {
+    let parses_for_any_player = |command_str: &str| {
+        symbol_tables
+            .values()
+            .any(|symbols| Command::parse_with(command_str, symbols).is_ok())
+    };
+
+    script
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            line.split(COMMAND_SEPARATOR)
+                .filter(move |command_str| !parses_for_any_player(command_str))
+                .filter_map(|command_str| Command::from_str(command_str).err())
+                .map(move |err| (index + 1, err))
+        })
+        .collect()
+}
+
 fn player_id_from_str(id_str: &str) -> Result<PlayerID, CommandParseError> {
     if !id_str.starts_with("P") || id_str.starts_with("P0") {
         Err(CommandParseError::InvalidPlayerID {
@@ -128,48 +382,253 @@ fn player_id_from_str(id_str: &str) -> Result<PlayerID, CommandParseError> {
     }
 }
 
-fn placement_from_str(placement_str: &str) -> Result<Placement, CommandParseError> {
+fn letter_from_str(
+    letter_str: &str,
+    symbols: &SymbolTable,
+) -> Result<ScrabbleLetter, CommandParseError> {
+    let mut letters = letter_str.chars();
+
+    match (
+        letters.next().and_then(|glyph| symbols.letter(glyph)),
+        letters.next(),
+    ) {
+        (Some(letter), None) => Ok(letter),
+        _ => Err(CommandParseError::InvalidLetters {
+            letters: letter_str.to_string(),
+        }),
+    }
+}
+
+fn position_from_str(position_str: &str) -> Result<Position, CommandParseError> {
+    let coordinates = position_str
+        .split(';')
+        .map(|coordinate| {
+            coordinate
+                .parse()
+                .ok()
+                .filter(|coordinate| (0..=MAX_COORDINATE).contains(coordinate))
+        })
+        .collect::<Option<Vec<isize>>>();
+
+    match coordinates.as_deref() {
+        Some([x, y]) => Ok((*x, *y)),
+        _ => Err(CommandParseError::InvalidPosition {
+            position: position_str.to_string(),
+        }),
+    }
+}
+
+fn placement_from_str(
+    placement_str: &str,
+    symbols: &SymbolTable,
+) -> Result<Placement, CommandParseError> {
     let invalid_placement_err = CommandParseError::InvalidPlacement {
         placement: placement_str.to_string(),
     };
+    let coordinate_from_str = |coordinate_str: &str| -> Result<isize, CommandParseError> {
+        coordinate_str
+            .parse()
+            .ok()
+            .filter(|coordinate| (-MAX_COORDINATE..=MAX_COORDINATE).contains(coordinate))
+            .ok_or_else(|| invalid_placement_err.clone())
+    };
 
-    if let [letters, start_x, start_y, direction] =
-        placement_str.split(';').collect::<Vec<&str>>()[..]
-    {
-        //TODO: prevent input of negative numbers!!!
-        let start_x: isize = start_x.parse().map_err(|_| invalid_placement_err.clone())?;
-        let start_y: isize = start_y.parse().map_err(|_| invalid_placement_err.clone())?;
+    match placement_str.split(';').collect::<Vec<&str>>()[..] {
+        [letters, start_x, start_y, direction] => {
+            let start_pos = (coordinate_from_str(start_x)?, coordinate_from_str(start_y)?);
+            let letters = letters_from_str(letters, symbols, &invalid_placement_err)?;
 
-        if letters.len() < 1 || letters.len() > 3 {
-            return Err(invalid_placement_err);
+            Ok(Placement::new(
+                &letters,
+                &start_pos,
+                &direction_from_str(direction)?,
+            ))
         }
+        [letters, start_x, start_y, end_x, end_y] => {
+            let start_pos = (coordinate_from_str(start_x)?, coordinate_from_str(start_y)?);
+            let end_pos = (coordinate_from_str(end_x)?, coordinate_from_str(end_y)?);
+            let letters = letters_from_str(letters, symbols, &invalid_placement_err)?;
+
+            // spans have to be straight and run from the start to the end position
+            let (span_length, direction) = if start_pos.1 == end_pos.1 {
+                (end_pos.0 - start_pos.0 + 1, Direction::Horizontal)
+            } else if start_pos.0 == end_pos.0 {
+                (end_pos.1 - start_pos.1 + 1, Direction::Vertical)
+            } else {
+                return Err(invalid_placement_err);
+            };
 
-        match direction {
-            "H" => Ok(Placement {
-                letters: letters
-                    .chars()
-                    .map(ScrabbleLetter::from_char)
-                    .collect::<Option<Vec<ScrabbleLetter>>>()
-                    .ok_or(CommandParseError::InvalidLetters {
-                        letters: letters.to_string(),
-                    })?,
-                start_pos: (start_x, start_y),
-                direction: Direction::Horizontal,
-            }),
-            "V" => Ok(Placement {
-                letters: letters
-                    .chars()
-                    .map(ScrabbleLetter::from_char)
-                    .collect::<Option<Vec<ScrabbleLetter>>>()
-                    .ok_or(CommandParseError::InvalidLetters {
-                        letters: letters.to_string(),
-                    })?,
-                start_pos: (start_x, start_y),
-                direction: Direction::Vertical,
-            }),
-            _ => Err(invalid_placement_err),
+            if span_length != letters.len() as isize {
+                return Err(invalid_placement_err);
+            }
+            Ok(Placement::new(&letters, &start_pos, &direction))
         }
-    } else {
-        Err(invalid_placement_err)
+        _ => Err(invalid_placement_err),
+    }
+}
+
+fn direction_from_str(direction_str: &str) -> Result<Direction, CommandParseError> {
+    match direction_str {
+        "H" | "h" | "right" | "horizontal" => Ok(Direction::Horizontal),
+        "V" | "v" | "down" | "vertical" => Ok(Direction::Vertical),
+        _ => Err(CommandParseError::InvalidDirection {
+            direction: direction_str.to_string(),
+        }),
+    }
+}
+
+fn letters_from_str(
+    letters_str: &str,
+    symbols: &SymbolTable,
+    invalid_placement_err: &CommandParseError,
+) -> Result<Vec<ScrabbleLetter>, CommandParseError> {
+    // glyphs from symbol tables can take more than one byte
+    let letter_count = letters_str.chars().count();
+    if letter_count == 0 || letter_count > MAX_PLACEMENT_LENGTH {
+        return Err(invalid_placement_err.clone());
+    }
+
+    letters_str
+        .chars()
+        .map(|glyph| symbols.letter(glyph))
+        .collect::<Option<Vec<ScrabbleLetter>>>()
+        .ok_or(CommandParseError::InvalidLetters {
+            letters: letters_str.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(command_str: &str) -> Result<Placement, CommandParseError> {
+        match command_str.parse::<Command>()? {
+            Command::Place(placement) => Ok(placement),
+            command => panic!("{:?} isn't a placement", command),
+        }
+    }
+
+    #[test]
+    fn placements_can_be_given_by_start_and_end() {
+        let horizontal = placement("place 12+;2;3;4;3").unwrap();
+        assert_eq!(horizontal.start_pos, (2, 3));
+        assert!(matches!(horizontal.direction, Direction::Horizontal));
+
+        let vertical = placement("place 12+;2;3;2;5").unwrap();
+        assert_eq!(vertical.start_pos, (2, 3));
+        assert!(matches!(vertical.direction, Direction::Vertical));
+    }
+
+    #[test]
+    fn diagonal_placements_are_rejected() {
+        assert!(matches!(
+            placement("place 12+;2;3;4;5"),
+            Err(CommandParseError::InvalidPlacement { .. })
+        ));
+    }
+
+    #[test]
+    fn coordinates_beyond_max_coordinate_are_rejected() {
+        for command_str in [
+            "place 12+;65536;0;H",
+            "place 12+;0;-65536;V",
+            "place 12+;99999999999999999999;0;H",
+        ] {
+            assert!(matches!(
+                placement(command_str),
+                Err(CommandParseError::InvalidPlacement { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn every_direction_synonym_is_accepted() {
+        for (direction, expected) in [
+            ("H", "12+;0;0;H"),
+            ("h", "12+;0;0;H"),
+            ("right", "12+;0;0;H"),
+            ("horizontal", "12+;0;0;H"),
+            ("V", "12+;0;0;V"),
+            ("v", "12+;0;0;V"),
+            ("down", "12+;0;0;V"),
+            ("vertical", "12+;0;0;V"),
+        ] {
+            let command_str = format!("place 12+;0;0;{}", direction);
+            assert_eq!(placement(&command_str).unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn unknown_directions_are_rejected() {
+        assert!(matches!(
+            placement("place 12+;0;0;diagonal"),
+            Err(CommandParseError::InvalidDirection { direction }) if direction == "diagonal"
+        ));
+    }
+
+    #[test]
+    fn check_script_reports_the_line_of_a_bad_command() {
+        let script = "place 12+;0;0;H\nscore P1 && print\nplace 34*;0;2;X\nprint\n";
+
+        let errors = check_script(script, &BTreeMap::new());
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            (3, CommandParseError::InvalidDirection { .. })
+        ));
+    }
+
+    #[test]
+    fn check_script_accepts_the_glyphs_of_every_player() {
+        let mut symbol_tables = BTreeMap::new();
+        symbol_tables.insert(1, "١1,٢2".parse::<SymbolTable>().unwrap());
+        let script = "place 12+;0;0;H\nplace ١٢+;0;1;H\nplace 一+;0;2;H\n";
+
+        let errors = check_script(script, &symbol_tables);
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            (3, CommandParseError::InvalidLetters { .. })
+        ));
+    }
+
+    #[test]
+    fn every_player_types_with_their_own_digits() {
+        let arabic_indic: SymbolTable = "١1,٢2".parse().unwrap();
+        let chinese: SymbolTable = "一1,二2".parse().unwrap();
+        let placement_with = |command_str: &str, symbols: &SymbolTable| match Command::parse_with(
+            command_str,
+            symbols,
+        )? {
+            Command::Place(placement) => Ok(placement.to_string()),
+            command => panic!("{:?} isn't a placement", command),
+        };
+
+        assert_eq!(
+            placement_with("place ١٢+;0;0;H", &arabic_indic).unwrap(),
+            "12+;0;0;H"
+        );
+        assert_eq!(
+            placement_with("place 一二+;0;0;H", &chinese).unwrap(),
+            "12+;0;0;H"
+        );
+        assert!(matches!(
+            placement_with("place ١٢+;0;0;H", &chinese),
+            Err(CommandParseError::InvalidLetters { .. })
+        ));
+    }
+
+    #[test]
+    fn notes_keep_the_rest_of_the_line() {
+        assert!(matches!(
+            "note 1 2 a good spot for 12+".parse::<Command>(),
+            Ok(Command::Note((1, 2), text)) if text == "a good spot for 12+"
+        ));
+        assert!(matches!(
+            "note 1 2".parse::<Command>(),
+            Err(CommandParseError::InvalidArgumentCount { .. })
+        ));
     }
 }
@@ -0,0 +1,779 @@
+use crate::scrabble_base_types::{PlayerID, ScrabbleLetter, SymbolTable};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub const DEFAULT_EMPTY_GLYPH: char = '.';
+
+#[derive(Debug, Clone)]
+pub enum ConfigParseError {
+    UnreadableFile {
+        path: String,
+        cause: String,
+    },
+    MalformedLine {
+        line: usize,
+        content: String,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for ConfigParseError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigParseError::UnreadableFile { path, cause } => {
+                write!(
+                    formatter,
+                    "Error: The config file '{}' couldn't be read: {}",
+                    path, cause
+                )
+            }
+            ConfigParseError::MalformedLine { line, content } => write!(
+                formatter,
+                "Error: Line {} of the config ('{}') is not of the form key=value!",
+                line, content
+            ),
+            ConfigParseError::UnknownKey { line, key } => {
+                write!(
+                    formatter,
+                    "Error: Line {} of the config contains the unknown key '{}'!",
+                    line, key
+                )
+            }
+            ConfigParseError::InvalidValue { line, key, value } => write!(
+                formatter,
+                "Error: Line {} of the config contains the invalid value '{}' for '{}'!",
+                line, value, key
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigParseError {}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ScoringMode {
+    TermValue,
+    TileSum,
+}
+
+impl std::fmt::Display for ScoringMode {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoringMode::TermValue => write!(formatter, "term_value"),
+            ScoringMode::TileSum => write!(formatter, "tile_sum"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OwnershipRule {
+    Majority,
+    Split,
+}
+
+impl std::fmt::Display for OwnershipRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OwnershipRule::Majority => write!(formatter, "majority"),
+            OwnershipRule::Split => write!(formatter, "split"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ZeroRule {
+    Regular,
+    VoidsTerm,
+    Penalty,
+}
+
+impl std::fmt::Display for ZeroRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZeroRule::Regular => write!(formatter, "regular"),
+            ZeroRule::VoidsTerm => write!(formatter, "void"),
+            ZeroRule::Penalty => write!(formatter, "penalty"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OpeningRule {
+    Free,
+    CenterOperator,
+}
+
+impl std::fmt::Display for OpeningRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpeningRule::Free => write!(formatter, "free"),
+            OpeningRule::CenterOperator => write!(formatter, "center_operator"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CrossTermRule {
+    Score,
+    Validate,
+    Ignore,
+}
+
+impl std::fmt::Display for CrossTermRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrossTermRule::Score => write!(formatter, "score"),
+            CrossTermRule::Validate => write!(formatter, "validate"),
+            CrossTermRule::Ignore => write!(formatter, "ignore"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BagFormat {
+    Raw,
+    Spaced,
+    Counts,
+}
+
+impl std::fmt::Display for BagFormat {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BagFormat::Raw => write!(formatter, "raw"),
+            BagFormat::Spaced => write!(formatter, "spaced"),
+            BagFormat::Counts => write!(formatter, "counts"),
+        }
+    }
+}
+
+impl FromStr for BagFormat {
+    type Err = ();
+
+    fn from_str(format_str: &str) -> Result<Self, Self::Err> {
+        match format_str {
+            "raw" => Ok(BagFormat::Raw),
+            "spaced" => Ok(BagFormat::Spaced),
+            "counts" => Ok(BagFormat::Counts),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EdgeRule {
+    Strict,
+    Clip,
+}
+
+impl std::fmt::Display for EdgeRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdgeRule::Strict => write!(formatter, "strict"),
+            EdgeRule::Clip => write!(formatter, "clip"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VictoryRule {
+    Score,
+    // the game ends with a full board and the most owned cells win
+    Territory,
+}
+
+impl std::fmt::Display for VictoryRule {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VictoryRule::Score => write!(formatter, "score"),
+            VictoryRule::Territory => write!(formatter, "territory"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Notation {
+    #[default]
+    Postfix,
+    Infix,
+}
+
+impl std::fmt::Display for Notation {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Notation::Postfix => write!(formatter, "postfix"),
+            Notation::Infix => write!(formatter, "infix"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StartingPlayer {
+    Fixed(PlayerID),
+    // drawn with the game RNG, so a seed makes it reproducible
+    Random,
+}
+
+impl std::fmt::Display for StartingPlayer {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartingPlayer::Fixed(player_id) => write!(formatter, "{}", player_id + 1),
+            StartingPlayer::Random => write!(formatter, "random"),
+        }
+    }
+}
+
+// players are numbered from 1 like in the P1, P2 ... notation
+impl FromStr for StartingPlayer {
+    type Err = ();
+
+    fn from_str(player_str: &str) -> Result<Self, Self::Err> {
+        match player_str {
+            "random" => Ok(StartingPlayer::Random),
+            _ => match player_str.parse::<PlayerID>() {
+                Ok(player) if player > 0 => Ok(StartingPlayer::Fixed(player - 1)),
+                _ => Err(()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    pub board_size: usize,
+    pub rack_size: Option<usize>,
+    pub scoring_mode: ScoringMode,
+    pub ownership_rule: OwnershipRule,
+    pub zero_rule: ZeroRule,
+    pub zero_penalty: i32,
+    pub opening_rule: OpeningRule,
+    pub allowed_operators: Vec<ScrabbleLetter>,
+    pub seed: Option<u64>,
+    pub empty_glyph: char,
+    pub require_digit: bool,
+    pub cross_terms: CrossTermRule,
+    pub reversible_terms: bool,
+    pub bag_format: BagFormat,
+    pub unique_term_values: bool,
+    pub max_operand: Option<i64>,
+    pub relative_coordinates: bool,
+    pub enforce_pool_limits: bool,
+    pub edge_rule: EdgeRule,
+    pub no_repeated_terms: bool,
+    pub trace_errors: bool,
+    pub victory_rule: VictoryRule,
+    pub repetition_limit: Option<usize>,
+    pub fold_digits: bool,
+    pub starting_player: StartingPlayer,
+    pub notation: Notation,
+    // only for infix terms, see EvaluationRules
+    pub implicit_multiplication: bool,
+    pub equations: bool,
+    // glyph overrides for the placements of single players
+    pub symbol_tables: BTreeMap<PlayerID, SymbolTable>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            board_size: 10,
+            rack_size: None,
+            scoring_mode: ScoringMode::TermValue,
+            ownership_rule: OwnershipRule::Majority,
+            zero_rule: ZeroRule::Regular,
+            zero_penalty: 1,
+            opening_rule: OpeningRule::Free,
+            allowed_operators: vec![
+                ScrabbleLetter::Plus,
+                ScrabbleLetter::Minus,
+                ScrabbleLetter::Dot,
+                ScrabbleLetter::Slash,
+                ScrabbleLetter::Percent,
+                ScrabbleLetter::Caret,
+            ],
+            seed: None,
+            empty_glyph: DEFAULT_EMPTY_GLYPH,
+            require_digit: false,
+            cross_terms: CrossTermRule::Score,
+            reversible_terms: false,
+            bag_format: BagFormat::Spaced,
+            unique_term_values: false,
+            max_operand: None,
+            relative_coordinates: false,
+            enforce_pool_limits: false,
+            edge_rule: EdgeRule::Strict,
+            no_repeated_terms: false,
+            trace_errors: false,
+            victory_rule: VictoryRule::Score,
+            repetition_limit: None,
+            fold_digits: false,
+            starting_player: StartingPlayer::Fixed(0),
+            notation: Notation::Postfix,
+            implicit_multiplication: false,
+            equations: false,
+            symbol_tables: BTreeMap::new(),
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn builder() -> GameConfigBuilder {
+        GameConfigBuilder {
+            config: GameConfig::default(),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<GameConfig, ConfigParseError> {
+        std::fs::read_to_string(path)
+            .map_err(|err| ConfigParseError::UnreadableFile {
+                path: path.to_string(),
+                cause: err.to_string(),
+            })?
+            .parse()
+    }
+}
+
+impl std::fmt::Display for GameConfig {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(formatter, "board_size = {}", self.board_size)?;
+        match self.rack_size {
+            Some(rack_size) => writeln!(formatter, "rack_size = {}", rack_size)?,
+            None => writeln!(formatter, "# rack_size not set")?,
+        }
+        writeln!(formatter, "scoring_mode = {}", self.scoring_mode)?;
+        writeln!(formatter, "ownership_rule = {}", self.ownership_rule)?;
+        writeln!(formatter, "zero_rule = {}", self.zero_rule)?;
+        writeln!(formatter, "zero_penalty = {}", self.zero_penalty)?;
+        writeln!(formatter, "opening_rule = {}", self.opening_rule)?;
+        writeln!(
+            formatter,
+            "operators = {}",
+            self.allowed_operators
+                .iter()
+                .map(ScrabbleLetter::to_string)
+                .collect::<String>()
+        )?;
+        match self.seed {
+            Some(seed) => writeln!(formatter, "seed = {}", seed)?,
+            None => writeln!(formatter, "# seed not set")?,
+        }
+        writeln!(formatter, "empty_glyph = {}", self.empty_glyph)?;
+        writeln!(formatter, "require_digit = {}", self.require_digit)?;
+        writeln!(formatter, "cross_terms = {}", self.cross_terms)?;
+        writeln!(formatter, "reversible_terms = {}", self.reversible_terms)?;
+        writeln!(formatter, "bag_format = {}", self.bag_format)?;
+        writeln!(
+            formatter,
+            "unique_term_values = {}",
+            self.unique_term_values
+        )?;
+        match self.max_operand {
+            Some(max_operand) => writeln!(formatter, "max_operand = {}", max_operand)?,
+            None => writeln!(formatter, "# max_operand not set")?,
+        }
+        writeln!(
+            formatter,
+            "relative_coordinates = {}",
+            self.relative_coordinates
+        )?;
+        writeln!(
+            formatter,
+            "enforce_pool_limits = {}",
+            self.enforce_pool_limits
+        )?;
+        writeln!(formatter, "edge_rule = {}", self.edge_rule)?;
+        writeln!(formatter, "no_repeated_terms = {}", self.no_repeated_terms)?;
+        writeln!(formatter, "trace_errors = {}", self.trace_errors)?;
+        writeln!(formatter, "victory_rule = {}", self.victory_rule)?;
+        match self.repetition_limit {
+            Some(repetition_limit) => {
+                writeln!(formatter, "repetition_limit = {}", repetition_limit)?
+            }
+            None => writeln!(formatter, "# repetition_limit not set")?,
+        }
+        writeln!(formatter, "fold_digits = {}", self.fold_digits)?;
+        writeln!(formatter, "starting_player = {}", self.starting_player)?;
+        writeln!(formatter, "notation = {}", self.notation)?;
+        writeln!(
+            formatter,
+            "implicit_multiplication = {}",
+            self.implicit_multiplication
+        )?;
+        writeln!(formatter, "equations = {}", self.equations)?;
+        for (player_id, symbol_table) in &self.symbol_tables {
+            writeln!(formatter, "symbols_p{} = {}", player_id + 1, symbol_table)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameConfig {
+    type Err = ConfigParseError;
+
+    fn from_str(config_str: &str) -> Result<Self, Self::Err> {
+        let mut config = GameConfig::default();
+
+        for (index, line) in config_str.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) =
+                line.split_once('=')
+                    .ok_or_else(|| ConfigParseError::MalformedLine {
+                        line: line_number,
+                        content: line.to_string(),
+                    })?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid_value_err = || ConfigParseError::InvalidValue {
+                line: line_number,
+                key: key.to_string(),
+                value: value.to_string(),
+            };
+
+            match key {
+                "board_size" => {
+                    config.board_size = value
+                        .parse()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or_else(invalid_value_err)?
+                }
+                "rack_size" => {
+                    config.rack_size = Some(value.parse().map_err(|_| invalid_value_err())?)
+                }
+                "scoring_mode" => {
+                    config.scoring_mode = match value {
+                        "term_value" => ScoringMode::TermValue,
+                        "tile_sum" => ScoringMode::TileSum,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "ownership_rule" => {
+                    config.ownership_rule = match value {
+                        "majority" => OwnershipRule::Majority,
+                        "split" => OwnershipRule::Split,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "zero_rule" => {
+                    config.zero_rule = match value {
+                        "regular" => ZeroRule::Regular,
+                        "void" => ZeroRule::VoidsTerm,
+                        "penalty" => ZeroRule::Penalty,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "zero_penalty" => {
+                    config.zero_penalty = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "opening_rule" => {
+                    config.opening_rule = match value {
+                        "free" => OpeningRule::Free,
+                        "center_operator" => OpeningRule::CenterOperator,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "operators" => {
+                    config.allowed_operators = value
+                        .chars()
+                        .map(ScrabbleLetter::from_char)
+                        .collect::<Option<Vec<ScrabbleLetter>>>()
+                        .filter(|operators| operators.iter().all(ScrabbleLetter::is_operator))
+                        .ok_or_else(invalid_value_err)?
+                }
+                "seed" => config.seed = Some(value.parse().map_err(|_| invalid_value_err())?),
+                "empty_glyph" => {
+                    config.empty_glyph = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "require_digit" => {
+                    config.require_digit = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "implicit_multiplication" => {
+                    config.implicit_multiplication =
+                        value.parse().map_err(|_| invalid_value_err())?
+                }
+                "equations" => config.equations = value.parse().map_err(|_| invalid_value_err())?,
+                "notation" => {
+                    config.notation = match value {
+                        "postfix" => Notation::Postfix,
+                        "infix" => Notation::Infix,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "starting_player" => {
+                    config.starting_player = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "fold_digits" => {
+                    config.fold_digits = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "repetition_limit" => {
+                    config.repetition_limit = Some(value.parse().map_err(|_| invalid_value_err())?)
+                }
+                "victory_rule" => {
+                    config.victory_rule = match value {
+                        "score" => VictoryRule::Score,
+                        "territory" => VictoryRule::Territory,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "trace_errors" => {
+                    config.trace_errors = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "no_repeated_terms" => {
+                    config.no_repeated_terms = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "edge_rule" => {
+                    config.edge_rule = match value {
+                        "strict" => EdgeRule::Strict,
+                        "clip" => EdgeRule::Clip,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                "enforce_pool_limits" => {
+                    config.enforce_pool_limits = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "relative_coordinates" => {
+                    config.relative_coordinates = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "max_operand" => {
+                    config.max_operand = Some(value.parse().map_err(|_| invalid_value_err())?)
+                }
+                "unique_term_values" => {
+                    config.unique_term_values = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "bag_format" => {
+                    config.bag_format = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "reversible_terms" => {
+                    config.reversible_terms = value.parse().map_err(|_| invalid_value_err())?
+                }
+                "cross_terms" => {
+                    config.cross_terms = match value {
+                        "score" => CrossTermRule::Score,
+                        "validate" => CrossTermRule::Validate,
+                        "ignore" => CrossTermRule::Ignore,
+                        _ => return Err(invalid_value_err()),
+                    }
+                }
+                // one key per player, like symbols_p2
+                _ if key.starts_with("symbols_p") => {
+                    let player_id = key["symbols_p".len()..]
+                        .parse::<PlayerID>()
+                        .ok()
+                        .filter(|player| *player > 0)
+                        .ok_or_else(|| ConfigParseError::UnknownKey {
+                            line: line_number,
+                            key: key.to_string(),
+                        })?;
+                    let symbol_table = value.parse().map_err(|_| invalid_value_err())?;
+                    config.symbol_tables.insert(player_id - 1, symbol_table);
+                }
+                _ => {
+                    return Err(ConfigParseError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub struct GameConfigBuilder {
+    config: GameConfig,
+}
+
+impl GameConfigBuilder {
+    pub fn board_size(mut self, board_size: usize) -> GameConfigBuilder {
+        self.config.board_size = board_size;
+        self
+    }
+
+    pub fn rack_size(mut self, rack_size: usize) -> GameConfigBuilder {
+        self.config.rack_size = Some(rack_size);
+        self
+    }
+
+    pub fn scoring_mode(mut self, scoring_mode: ScoringMode) -> GameConfigBuilder {
+        self.config.scoring_mode = scoring_mode;
+        self
+    }
+
+    pub fn ownership_rule(mut self, ownership_rule: OwnershipRule) -> GameConfigBuilder {
+        self.config.ownership_rule = ownership_rule;
+        self
+    }
+
+    pub fn zero_rule(mut self, zero_rule: ZeroRule) -> GameConfigBuilder {
+        self.config.zero_rule = zero_rule;
+        self
+    }
+
+    pub fn zero_penalty(mut self, zero_penalty: i32) -> GameConfigBuilder {
+        self.config.zero_penalty = zero_penalty;
+        self
+    }
+
+    pub fn opening_rule(mut self, opening_rule: OpeningRule) -> GameConfigBuilder {
+        self.config.opening_rule = opening_rule;
+        self
+    }
+
+    pub fn allowed_operators(mut self, allowed_operators: &[ScrabbleLetter]) -> GameConfigBuilder {
+        self.config.allowed_operators = allowed_operators.to_vec();
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> GameConfigBuilder {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    pub fn empty_glyph(mut self, empty_glyph: char) -> GameConfigBuilder {
+        self.config.empty_glyph = empty_glyph;
+        self
+    }
+
+    pub fn require_digit(mut self, require_digit: bool) -> GameConfigBuilder {
+        self.config.require_digit = require_digit;
+        self
+    }
+
+    pub fn cross_terms(mut self, cross_terms: CrossTermRule) -> GameConfigBuilder {
+        self.config.cross_terms = cross_terms;
+        self
+    }
+
+    pub fn reversible_terms(mut self, reversible_terms: bool) -> GameConfigBuilder {
+        self.config.reversible_terms = reversible_terms;
+        self
+    }
+
+    pub fn bag_format(mut self, bag_format: BagFormat) -> GameConfigBuilder {
+        self.config.bag_format = bag_format;
+        self
+    }
+
+    pub fn unique_term_values(mut self, unique_term_values: bool) -> GameConfigBuilder {
+        self.config.unique_term_values = unique_term_values;
+        self
+    }
+
+    pub fn max_operand(mut self, max_operand: i64) -> GameConfigBuilder {
+        self.config.max_operand = Some(max_operand);
+        self
+    }
+
+    pub fn relative_coordinates(mut self, relative_coordinates: bool) -> GameConfigBuilder {
+        self.config.relative_coordinates = relative_coordinates;
+        self
+    }
+
+    pub fn enforce_pool_limits(mut self, enforce_pool_limits: bool) -> GameConfigBuilder {
+        self.config.enforce_pool_limits = enforce_pool_limits;
+        self
+    }
+
+    pub fn edge_rule(mut self, edge_rule: EdgeRule) -> GameConfigBuilder {
+        self.config.edge_rule = edge_rule;
+        self
+    }
+
+    pub fn no_repeated_terms(mut self, no_repeated_terms: bool) -> GameConfigBuilder {
+        self.config.no_repeated_terms = no_repeated_terms;
+        self
+    }
+
+    pub fn trace_errors(mut self, trace_errors: bool) -> GameConfigBuilder {
+        self.config.trace_errors = trace_errors;
+        self
+    }
+
+    pub fn victory_rule(mut self, victory_rule: VictoryRule) -> GameConfigBuilder {
+        self.config.victory_rule = victory_rule;
+        self
+    }
+
+    pub fn repetition_limit(mut self, repetition_limit: usize) -> GameConfigBuilder {
+        self.config.repetition_limit = Some(repetition_limit);
+        self
+    }
+
+    pub fn fold_digits(mut self, fold_digits: bool) -> GameConfigBuilder {
+        self.config.fold_digits = fold_digits;
+        self
+    }
+
+    pub fn starting_player(mut self, starting_player: StartingPlayer) -> GameConfigBuilder {
+        self.config.starting_player = starting_player;
+        self
+    }
+
+    pub fn notation(mut self, notation: Notation) -> GameConfigBuilder {
+        self.config.notation = notation;
+        self
+    }
+
+    pub fn implicit_multiplication(mut self, implicit_multiplication: bool) -> GameConfigBuilder {
+        self.config.implicit_multiplication = implicit_multiplication;
+        self
+    }
+
+    pub fn equations(mut self, equations: bool) -> GameConfigBuilder {
+        self.config.equations = equations;
+        self
+    }
+
+    pub fn symbol_table(
+        mut self,
+        player_id: PlayerID,
+        symbol_table: SymbolTable,
+    ) -> GameConfigBuilder {
+        self.config.symbol_tables.insert(player_id, symbol_table);
+        self
+    }
+
+    pub fn build(self) -> GameConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys_are_reported_with_their_line() {
+        let result = "board_size = 7\nboard_colour = red\n".parse::<GameConfig>();
+
+        assert!(matches!(
+            result,
+            Err(ConfigParseError::UnknownKey { line: 2, ref key }) if key == "board_colour"
+        ));
+    }
+
+    #[test]
+    fn the_printed_config_lists_the_settings() {
+        let config = GameConfig::builder()
+            .board_size(7)
+            .scoring_mode(ScoringMode::TileSum)
+            .build();
+
+        let printed = config.to_string();
+
+        assert!(printed.contains("board_size = 7\n"));
+        assert!(printed.contains("scoring_mode = tile_sum\n"));
+    }
+}
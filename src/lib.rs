@@ -0,0 +1,8 @@
+pub mod command_parsing;
+pub mod game_config;
+pub mod random;
+pub mod scrabble;
+pub mod scrabble_base_types;
+pub mod term_evaluation;
+
+pub use term_evaluation::Term;
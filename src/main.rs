@@ -1,44 +1,138 @@
-mod command_parsing;
-mod scrabble;
-mod scrabble_base_types;
-mod term_evaluation;
+use std::io::{self, BufRead, IsTerminal, Write};
 
-use scrabble_base_types::ScrabbleLetter;
-use std::io::{self, BufRead};
-use std::str::FromStr;
-
-use scrabble::ScrabbleGame;
+use math_scrabble::command_parsing;
+use math_scrabble::game_config::{BagFormat, GameConfig, StartingPlayer, VictoryRule};
+use math_scrabble::scrabble::ScrabbleGame;
+use math_scrabble::scrabble_base_types::ScrabbleLetter;
 
 fn main() {
     let stdin = io::stdin();
-    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args = std::env::args().skip(1);
+    let mut config = GameConfig::default();
+    let mut letter_bag_args: Vec<String> = Vec::new();
+    let mut keep_going = false;
+    let mut quiet = false;
+    let mut tally = false;
+    let mut script_to_check: Option<String> = None;
+    let mut dealt_player_count: Option<usize> = None;
+    let mut bag_format_arg: Option<BagFormat> = None;
+    let mut starting_player_arg: Option<StartingPlayer> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                let Some(path) = args.next() else {
+                    println!("The option --config expects a path to a config file!");
+                    return;
+                };
+                match GameConfig::from_file(&path) {
+                    Ok(parsed_config) => config = parsed_config,
+                    Err(err) => {
+                        println!("{}", err);
+                        return;
+                    }
+                }
+            }
+            "--check-script" => {
+                let Some(path) = args.next() else {
+                    println!("The option --check-script expects a path to a script!");
+                    return;
+                };
+                script_to_check = Some(path);
+            }
+            "--deal" => {
+                let Some(player_count) = args.next().and_then(|count| count.parse().ok()) else {
+                    println!("The option --deal expects the number of players!");
+                    return;
+                };
+                dealt_player_count = Some(player_count);
+            }
+            "--bag-format" => {
+                let Some(bag_format) = args.next().and_then(|format| format.parse().ok()) else {
+                    println!("The option --bag-format expects raw, spaced or counts!");
+                    return;
+                };
+                bag_format_arg = Some(bag_format);
+            }
+            "--starting-player" => {
+                let Some(starting_player) = args.next().and_then(|player| player.parse().ok())
+                else {
+                    println!("The option --starting-player expects a player number or random!");
+                    return;
+                };
+                starting_player_arg = Some(starting_player);
+            }
+            "--keep-going" => keep_going = true,
+            "--quiet" => quiet = true,
+            "--tally" => tally = true,
+            _ => letter_bag_args.push(arg),
+        }
+    }
+
+    if let Some(path) = script_to_check {
+        let script = match std::fs::read_to_string(&path) {
+            Ok(script) => script,
+            Err(err) => {
+                println!("The script '{}' couldn't be read: {}", path, err);
+                return;
+            }
+        };
+        let errors = command_parsing::check_script(&script, &config.symbol_tables);
+        for (line, err) in &errors {
+            println!("Line {}: {}", line, err);
+        }
+        if errors.is_empty() {
+            println!("The script contains no invalid commands.");
+        }
+        return;
+    }
+
+    // the command line option wins over the config file, no matter the order
+    if let Some(bag_format) = bag_format_arg {
+        config.bag_format = bag_format;
+    }
+    if let Some(starting_player) = starting_player_arg {
+        config.starting_player = starting_player;
+    }
 
-    if args.len() < 2 {
-        println!("You need at least 2 players to play math scrabble!");
+    if dealt_player_count.is_some() && !letter_bag_args.is_empty() {
+        println!("Player bags can't be given when the racks are dealt!");
         return;
     }
 
-    let player_letter_bags: Vec<Option<Vec<ScrabbleLetter>>> = args
+    let player_letter_bags: Vec<Option<Vec<ScrabbleLetter>>> = letter_bag_args
         .into_iter()
-        .map(|letters_str| {
-            letters_str
-                .chars()
-                .map(|c| ScrabbleLetter::from_char(c))
-                .collect()
-        })
+        .map(|letters_str| letters_str.chars().map(ScrabbleLetter::from_char).collect())
         .collect();
     if player_letter_bags.iter().any(|bag| bag.is_none()) {
         println!("At least one of the player bags contains invalid letters!");
         return;
     }
     // validity already checked
-    let player_letter_bags_unwrapped = player_letter_bags
+    let player_letter_bags_unwrapped: Vec<Vec<ScrabbleLetter>> = player_letter_bags
         .into_iter()
         .map(|bag| bag.unwrap())
         .collect();
-    let mut scrabble_game = ScrabbleGame::<10>::new(&player_letter_bags_unwrapped);
+    let new_game = match dealt_player_count {
+        Some(player_count) => ScrabbleGame::with_dealt_racks(&config, player_count),
+        None => ScrabbleGame::new(&config, &player_letter_bags_unwrapped),
+    };
+    let mut scrabble_game = match new_game {
+        Ok(scrabble_game) => scrabble_game,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+
+    let show_prompt = !quiet && stdin.is_terminal();
+
+    'repl: loop {
+        if show_prompt {
+            print!("{}", prompt(&scrabble_game));
+            io::stdout().flush().expect("write err");
+        }
 
-    loop {
         let line = stdin
             .lock()
             .lines()
@@ -46,15 +140,114 @@ fn main() {
             .expect("no next line")
             .expect("read err");
 
-        let command = command_parsing::Command::from_str(line.as_str());
+        let mut has_moved = false;
+        for command_str in line.split(command_parsing::COMMAND_SEPARATOR) {
+            let symbols = scrabble_game.symbol_table(scrabble_game.current_player());
+            let command = command_parsing::Command::parse_with(command_str, &symbols);
+
+            let result = match command {
+                Err(err) => Err(err.to_string()),
+                Ok(command_parsing::Command::Quit) => break 'repl,
+                Ok(cmd) => scrabble_game
+                    .execute_command(&cmd)
+                    .map(|_| {
+                        let is_placement = matches!(cmd, command_parsing::Command::Place(_));
+                        if is_placement && tally {
+                            println!("{}", format_tally(&scrabble_game));
+                        }
+                        has_moved |= is_placement || matches!(cmd, command_parsing::Command::Pass);
+                    })
+                    .map_err(|err| err.to_string()),
+            };
+            if let Err(err) = result {
+                println!("{}", err);
+                if !keep_going {
+                    break;
+                }
+            }
+        }
 
-        match command {
-            Err(err) => println!("{}", err),
-            Ok(command_parsing::Command::Quit) => break,
-            Ok(cmd) => match scrabble_game.execute_command(&cmd) {
-                Err(err) => println!("{}", err),
-                Ok(_) => (),
-            },
+        if !has_moved {
+            continue;
+        }
+        let is_territory_game = config.victory_rule == VictoryRule::Territory;
+        if scrabble_game.is_drawn_by_repetition() {
+            println!("The same position occurred too often, the game is a draw!");
+        } else if is_territory_game && scrabble_game.is_board_full() {
+            println!("The board is full, the game is over!");
+        } else if scrabble_game.is_stuck() {
+            println!("No player can move anymore, the game is over!");
+        } else {
+            continue;
+        }
+        for (player_id, score) in scrabble_game.scores().iter().enumerate() {
+            println!("P{}: {}", player_id + 1, score);
         }
+        if is_territory_game {
+            print_territory_result(&scrabble_game);
+        }
+        break;
+    }
+}
+
+// shows whose turn it is
+fn prompt(scrabble_game: &ScrabbleGame) -> String {
+    format!("P{}> ", scrabble_game.current_player() + 1)
+}
+
+fn format_tally(scrabble_game: &ScrabbleGame) -> String {
+    scrabble_game
+        .scores()
+        .iter()
+        .enumerate()
+        .map(|(player_id, score)| format!("P{}:{}", player_id + 1, score))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn print_territory_result(scrabble_game: &ScrabbleGame) {
+    for (player_id, cells) in scrabble_game.owned_cells().iter().enumerate() {
+        println!("P{}: {} cells", player_id + 1, cells);
+    }
+    match scrabble_game.territory_winner() {
+        Some(player_id) => println!("P{} wins by territory!", player_id + 1),
+        None => println!("Nobody controls the most territory, the game is a draw!"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_with_two_players(config: &GameConfig) -> ScrabbleGame {
+        let bag = |letters_str: &str| -> Vec<ScrabbleLetter> {
+            letters_str
+                .chars()
+                .map(|letter| ScrabbleLetter::from_char(letter).expect("invalid letter in test"))
+                .collect()
+        };
+
+        ScrabbleGame::new(config, &[bag("12+"), bag("34*")]).unwrap()
+    }
+
+    #[test]
+    fn the_prompt_shows_the_player_to_move() {
+        let mut scrabble_game = game_with_two_players(&GameConfig::default());
+        assert_eq!(prompt(&scrabble_game), "P1> ");
+
+        scrabble_game
+            .execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        assert_eq!(prompt(&scrabble_game), "P2> ");
+    }
+
+    #[test]
+    fn the_tally_shows_the_scores_after_a_move() {
+        let mut scrabble_game = game_with_two_players(&GameConfig::default());
+        scrabble_game
+            .execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(format_tally(&scrabble_game), "P1:3 P2:0");
     }
 }
@@ -1,11 +1,18 @@
-use std::collections::HashMap;
-use std::hash::Hash;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 
 use crate::command_parsing::Command;
+use crate::game_config::{
+    BagFormat, CrossTermRule, EdgeRule, GameConfig, OpeningRule, ScoringMode, StartingPlayer,
+    VictoryRule, ZeroRule, DEFAULT_EMPTY_GLYPH,
+};
+use crate::random::Rng;
 use crate::scrabble_base_types::{
-    move_position, Direction, Placement, PlayerID, Position, ScrabbleLetter,
+    checked_move_position, default_tile_pool, move_position, Direction, Owner, Placement, PlayerID,
+    Position, ScrabbleLetter, SymbolTable, DEFAULT_RACK_SIZE, MAX_BOARD_SIZE, MAX_PLACEMENT_LENGTH,
 };
-use crate::term_evaluation::Term;
+use crate::term_evaluation::{EvaluationRules, Term};
 
 #[derive(Debug)]
 pub enum ScrabbleRuntimeError {
@@ -14,6 +21,16 @@ pub enum ScrabbleRuntimeError {
     InvalidPlacement(String),
     MissingLetters,
     BlockedSpace,
+    NoTermsFormed,
+    NothingToUndo,
+    RackSizeExceeded(usize),
+    NotEnoughTiles(usize),
+    EmptyCell(Position),
+    LockedCell(Position),
+    FileError { path: String, cause: String },
+    InvalidCsv(String),
+    InvalidMoveNotation { line: usize, cause: String },
+    GameAlreadyStarted,
 }
 
 impl std::fmt::Display for ScrabbleRuntimeError {
@@ -48,12 +65,114 @@ impl std::fmt::Display for ScrabbleRuntimeError {
                     "Error: The placement is out of bounds or tried to overwrite existing letters!"
                 )
             }
+            ScrabbleRuntimeError::RackSizeExceeded(rack_size) => {
+                write!(
+                    formatter,
+                    "Error: A rack can't hold more than {} letters!",
+                    rack_size
+                )
+            }
+            ScrabbleRuntimeError::NotEnoughTiles(available) => {
+                write!(
+                    formatter,
+                    "Error: Only {} tiles are available to deal from!",
+                    available
+                )
+            }
+            ScrabbleRuntimeError::NothingToUndo => {
+                write!(formatter, "Error: There is no placement left to undo!")
+            }
+            ScrabbleRuntimeError::EmptyCell(position) => {
+                write!(formatter, "Error: The cell {:?} is empty!", position)
+            }
+            ScrabbleRuntimeError::LockedCell(position) => {
+                write!(formatter, "Error: The tile at {:?} is locked!", position)
+            }
+            ScrabbleRuntimeError::FileError { path, cause } => {
+                write!(formatter, "Error: Couldn't access '{}': {}", path, cause)
+            }
+            ScrabbleRuntimeError::InvalidCsv(cause) => {
+                write!(formatter, "Error: Invalid board csv: {}", cause)
+            }
+            ScrabbleRuntimeError::InvalidMoveNotation { line, cause } => {
+                write!(formatter, "Error: Line {} of the moves: {}", line, cause)
+            }
+            ScrabbleRuntimeError::GameAlreadyStarted => write!(
+                formatter,
+                "Error: This command is only allowed before the first placement!"
+            ),
+            ScrabbleRuntimeError::NoTermsFormed => {
+                write!(
+                    formatter,
+                    "Error: The placement doesn't form any terms, terms of length 1 are not allowed!"
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for ScrabbleRuntimeError {}
 
+#[derive(Debug)]
+pub enum GameInitError {
+    NotEnoughPlayers(usize),
+    BagTooLarge {
+        player: PlayerID,
+        rack_size: usize,
+    },
+    NotEnoughTiles {
+        needed: usize,
+        available: usize,
+    },
+    PoolExceeded {
+        letter: ScrabbleLetter,
+        count: usize,
+        limit: usize,
+    },
+    InvalidConfig(String),
+}
+
+impl std::fmt::Display for GameInitError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameInitError::NotEnoughPlayers(min_players) => write!(
+                formatter,
+                "Error: You need at least {} players to play math scrabble!",
+                min_players
+            ),
+            GameInitError::BagTooLarge { player, rack_size } => write!(
+                formatter,
+                "Error: The bag of P{} contains more than {} letters!",
+                player + 1,
+                rack_size
+            ),
+            GameInitError::NotEnoughTiles { needed, available } => write!(
+                formatter,
+                "Error: Dealing the racks needs {} tiles, but the pile only holds {}!",
+                needed, available
+            ),
+            GameInitError::PoolExceeded {
+                letter,
+                count,
+                limit,
+            } => write!(
+                formatter,
+                "Error: The players hold {} '{}' tiles, but the pool only has {}!",
+                count, letter, limit
+            ),
+            GameInitError::InvalidConfig(cause) => {
+                write!(formatter, "Error: Invalid config: {}", cause)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameInitError {}
+
+const MIN_PLAYERS: usize = 2;
+
+const MAX_LISTED_DROPS: usize = 20;
+
 #[repr(isize)]
 #[derive(Copy, Clone)]
 enum TermDirection {
@@ -61,28 +180,226 @@ enum TermDirection {
     Increasing = 1,
 }
 
-pub struct ScrabbleGame<const N: usize> {
+#[derive(Clone)]
+struct MoveRecord {
+    player: PlayerID,
+    // counts the moves of this player only, starting at 1
+    player_move_number: usize,
+    placement: Placement,
+    score_changes: Vec<(PlayerID, isize)>,
+    term_scores: Vec<(Term, i64, i64)>,
+    // points the moving player got
+    points: isize,
+    was_first_placement: bool,
+    // state hash at the end of the move, it stops counting as seen when the move is taken back
+    reached_state: u64,
+}
+
+struct PlacementOutcome {
+    board: GameBoard,
+    score_changes: Vec<(PlayerID, isize)>,
+    // (term, value, score) for every term formed by the placement
+    term_scores: Vec<(Term, i64, i64)>,
+}
+
+impl PlacementOutcome {
+    fn points_for(&self, player_id: PlayerID) -> isize {
+        self.score_changes
+            .iter()
+            .filter(|(receiver, _)| *receiver == player_id)
+            .map(|(_, points)| points)
+            .sum()
+    }
+}
+
+#[derive(Clone)]
+pub struct ScrabbleGame {
+    config: GameConfig,
     players: Vec<Player>,
     current_player: usize,
-    board: GameBoard<N>,
+    board: GameBoard,
     is_first_placement: bool,
+    history: Vec<MoveRecord>,
+    rng: Rng,
+    verbose: bool,
+    // shared pool the deal command and dealt games draw from
+    pile: Vec<ScrabbleLetter>,
+    // term value announced once a placement brings it onto the board
+    goal: Option<i64>,
+    // how often each state hash occurred at the end of a turn
+    seen_states: HashMap<u64, usize>,
+    // teaching notes on cells, they don't affect the game
+    notes: BTreeMap<Position, String>,
 }
 
-impl<const N: usize> ScrabbleGame<N> {
-    pub fn new(player_bags: &Vec<Vec<ScrabbleLetter>>) -> ScrabbleGame<N> {
-        ScrabbleGame {
-            players: player_bags.into_iter().map(Player::new).collect(),
-            current_player: 0,
-            board: GameBoard::new(),
+impl ScrabbleGame {
+    pub fn new(
+        config: &GameConfig,
+        player_bags: &[Vec<ScrabbleLetter>],
+    ) -> Result<ScrabbleGame, GameInitError> {
+        if player_bags.len() < MIN_PLAYERS {
+            return Err(GameInitError::NotEnoughPlayers(MIN_PLAYERS));
+        }
+        if let Some(rack_size) = config.rack_size {
+            if let Some(player) = player_bags.iter().position(|bag| bag.len() > rack_size) {
+                return Err(GameInitError::BagTooLarge { player, rack_size });
+            }
+        }
+        if config.board_size == 0 {
+            return Err(GameInitError::InvalidConfig(
+                "The board needs at least one cell!".to_string(),
+            ));
+        }
+        if config.board_size > MAX_BOARD_SIZE {
+            return Err(GameInitError::InvalidConfig(format!(
+                "The board can be at most {0}x{0}, not {1}x{1}!",
+                MAX_BOARD_SIZE, config.board_size
+            )));
+        }
+        if let Some(letter) = config
+            .allowed_operators
+            .iter()
+            .find(|letter| !letter.is_operator())
+        {
+            return Err(GameInitError::InvalidConfig(format!(
+                "'{}' is not an operator!",
+                letter
+            )));
+        }
+
+        if let StartingPlayer::Fixed(player_id) = config.starting_player {
+            if player_id >= player_bags.len() {
+                return Err(GameInitError::InvalidConfig(format!(
+                    "There is no P{} to start the game!",
+                    player_id + 1
+                )));
+            }
+        }
+
+        if let Some(player_id) = config
+            .symbol_tables
+            .keys()
+            .find(|player_id| **player_id >= player_bags.len())
+        {
+            return Err(GameInitError::InvalidConfig(format!(
+                "There is no P{} to use the symbol table!",
+                player_id + 1
+            )));
+        }
+
+        // racks brought without pool limits aren't taken from the pool, so there is no pile to draw from
+        let brings_own_racks = player_bags.iter().any(|bag| !bag.is_empty());
+        let mut pile = if brings_own_racks && !config.enforce_pool_limits {
+            Vec::new()
+        } else {
+            default_tile_pool()
+        };
+        if config.enforce_pool_limits {
+            let limits = frequency(&pile);
+            let mut counts = frequency(&player_bags.concat());
+            counts.sort();
+            for (letter, count) in counts {
+                let limit = limits
+                    .iter()
+                    .find(|(tile, _)| *tile == letter)
+                    .map_or(0, |(_, limit)| *limit);
+                if count > limit {
+                    return Err(GameInitError::PoolExceeded {
+                        letter,
+                        count,
+                        limit,
+                    });
+                }
+            }
+
+            // tiles held by the players are taken out of the pile
+            for letter in player_bags.iter().flatten() {
+                let index = pile
+                    .iter()
+                    .position(|tile| tile == letter)
+                    .expect("BUG: pool limits checked above!");
+                pile.swap_remove(index);
+            }
+        }
+
+        let mut rng = config.seed.map(Rng::new).unwrap_or_else(Rng::from_time);
+        let current_player = match config.starting_player {
+            StartingPlayer::Fixed(player_id) => player_id,
+            StartingPlayer::Random => rng.below(player_bags.len()),
+        };
+
+        let mut game = ScrabbleGame {
+            config: config.clone(),
+            players: player_bags.iter().map(|bag| Player::new(bag)).collect(),
+            current_player,
+            board: GameBoard::new(config.board_size),
             is_first_placement: true,
+            history: Vec::new(),
+            rng,
+            verbose: false,
+            pile,
+            goal: None,
+            seen_states: HashMap::new(),
+            notes: BTreeMap::new(),
+        };
+        // the opening position counts as seen, passing back to it is a repetition
+        game.record_state();
+
+        Ok(game)
+    }
+
+    // deals every player a rack of the configured size from the shared pile
+    pub fn with_dealt_racks(
+        config: &GameConfig,
+        player_count: usize,
+    ) -> Result<ScrabbleGame, GameInitError> {
+        let mut game = ScrabbleGame::new(config, &vec![Vec::new(); player_count])?;
+        let rack_size = config.rack_size.unwrap_or(DEFAULT_RACK_SIZE);
+
+        if rack_size * player_count > game.pile.len() {
+            return Err(GameInitError::NotEnoughTiles {
+                needed: rack_size * player_count,
+                available: game.pile.len(),
+            });
         }
+        for player_id in 0..player_count {
+            game.players[player_id].letter_bag = game.draw(rack_size);
+        }
+        // the empty racks were never played with, the dealt ones are the opening position
+        game.seen_states.clear();
+        game.record_state();
+
+        Ok(game)
+    }
+
+    pub fn with_default_config(
+        player_bags: &[Vec<ScrabbleLetter>],
+    ) -> Result<ScrabbleGame, GameInitError> {
+        ScrabbleGame::new(&GameConfig::default(), player_bags)
     }
 
     pub fn execute_command(&mut self, cmd: &Command) -> Result<(), ScrabbleRuntimeError> {
         match cmd {
             Command::Quit => unreachable!("Bug: Quit commands shouldn't be handled by the game!"),
             Command::Print => {
-                print!("{}", self.board);
+                print!(
+                    "{}",
+                    self.board.renderer(RenderOptions {
+                        empty_glyph: self.config.empty_glyph,
+                        symbols: self.symbol_table(self.current_player),
+                        ..RenderOptions::default()
+                    })
+                );
+                Ok(())
+            }
+            Command::PrintCropped => {
+                print!(
+                    "{}",
+                    self.board.render_cropped(
+                        self.config.empty_glyph,
+                        &self.symbol_table(self.current_player)
+                    )
+                );
                 Ok(())
             }
             Command::Score(player_id) => {
@@ -99,70 +416,326 @@ impl<const N: usize> ScrabbleGame<N> {
                 } else {
                     println!(
                         "{}",
-                        self.players[*player_id]
-                            .letter_bag
-                            .iter()
-                            .map(ScrabbleLetter::to_string)
-                            .collect::<String>()
+                        format_bag(&self.players[*player_id].letter_bag, self.config.bag_format)
                     );
                     Ok(())
                 }
             }
-            Command::Place(placement) => self.place_on_board(placement),
+            Command::Place(placement) => {
+                let mut placement = self.absolute_placement(placement)?;
+                if self.config.edge_rule == EdgeRule::Clip {
+                    placement = self.clipped_placement(&placement)?;
+                }
+                self.place_on_board(&placement)
+            }
+            Command::Undo => self.undo(),
+            Command::Pass => {
+                self.next_player();
+                self.record_state();
+                Ok(())
+            }
+            Command::Drops(letter) => {
+                let drops = self.drops(*letter);
+                for (position, points) in drops.iter().take(MAX_LISTED_DROPS) {
+                    println!("{:?}: {:+}", position, points);
+                }
+                if drops.len() > MAX_LISTED_DROPS {
+                    println!("... and {} more", drops.len() - MAX_LISTED_DROPS);
+                }
+                Ok(())
+            }
+            Command::Deal(count) => self.deal(*count),
+            Command::Territory => {
+                self.print_territory();
+                Ok(())
+            }
+            Command::Config => {
+                print!("{}", self.config);
+                Ok(())
+            }
+            Command::ExportCsv(path) => std::fs::write(path, self.board.to_csv()).map_err(|err| {
+                ScrabbleRuntimeError::FileError {
+                    path: path.clone(),
+                    cause: err.to_string(),
+                }
+            }),
+            Command::ImportCsv(path) => self.import_csv(path),
+            Command::ExportMoves(path) => {
+                std::fs::write(path, self.move_notation()).map_err(|err| {
+                    ScrabbleRuntimeError::FileError {
+                        path: path.clone(),
+                        cause: err.to_string(),
+                    }
+                })
+            }
+            Command::ImportMoves(path) => {
+                let notation = std::fs::read_to_string(path).map_err(|err| {
+                    ScrabbleRuntimeError::FileError {
+                        path: path.clone(),
+                        cause: err.to_string(),
+                    }
+                })?;
+                self.replay_moves(&notation)
+            }
+            Command::Explain(letter) => {
+                println!("{}", self.explain(*letter));
+                Ok(())
+            }
+            Command::Goal(goal) => {
+                self.goal = Some(*goal);
+                Ok(())
+            }
+            Command::Seed(seed) => {
+                // reseeding during play would allow picking favourable draws
+                if !self.history.is_empty() {
+                    return Err(ScrabbleRuntimeError::GameAlreadyStarted);
+                }
+                self.rng = Rng::new(*seed);
+                Ok(())
+            }
+            Command::Stats => {
+                self.print_stats();
+                Ok(())
+            }
+            Command::Census => {
+                let mut census: Vec<(ScrabbleLetter, usize)> =
+                    self.tile_census().into_iter().collect();
+                census.sort();
+                for (letter, count) in census {
+                    println!("{}: {}", letter, count);
+                }
+                println!("tiles in the pile: {}", self.pile_remaining());
+                Ok(())
+            }
+            Command::Potential => {
+                println!("{}", self.potential());
+                Ok(())
+            }
+            Command::CanMove => {
+                for player_id in self.players_able_to_move() {
+                    println!("P{}", player_id + 1);
+                }
+                Ok(())
+            }
+            Command::Gap => {
+                println!("{}", self.gap(self.current_player)?);
+                Ok(())
+            }
+            Command::Lock(position) => self.board.lock(*position),
+            Command::Note(position, text) => {
+                self.board.try_get(*position)?;
+                self.notes.insert(*position, text.clone());
+                Ok(())
+            }
+            Command::Notes => {
+                for (position, text) in &self.notes {
+                    println!("{:?}: {}", position, text);
+                }
+                Ok(())
+            }
+            Command::Verbose(verbose) => {
+                self.verbose = *verbose;
+                Ok(())
+            }
+            Command::History => {
+                for (index, record) in self.history.iter().enumerate() {
+                    println!(
+                        "{}. P{} move {}: {} ({:+})",
+                        index + 1,
+                        record.player + 1,
+                        record.player_move_number,
+                        record.placement,
+                        record.points
+                    );
+                }
+                Ok(())
+            }
         }
     }
 
+    // with relative coordinates, placements after the first one are given relative to its start
+    fn absolute_placement(&self, placement: &Placement) -> Result<Placement, ScrabbleRuntimeError> {
+        let origin = match self.history.first() {
+            Some(first_move) if self.config.relative_coordinates => first_move.placement.start_pos,
+            _ => return Ok(placement.clone()),
+        };
+        let start_pos = (
+            origin.0 + placement.start_pos.0,
+            origin.1 + placement.start_pos.1,
+        );
+        if self.board.is_out_of_bounds(start_pos) {
+            return Err(ScrabbleRuntimeError::PositionOutOfBounds(start_pos));
+        }
+
+        Ok(Placement::new(
+            &placement.letters,
+            &start_pos,
+            &placement.direction,
+        ))
+    }
+
+    // drops the tiles outside of the board, they stay in the rack and the terms form as if they weren't placed
+    fn clipped_placement(&self, placement: &Placement) -> Result<Placement, ScrabbleRuntimeError> {
+        let in_bounds: Vec<(Position, ScrabbleLetter)> = placement
+            .letters
+            .iter()
+            .enumerate()
+            .map(|(offset, letter)| {
+                (
+                    move_position(placement.start_pos, offset as isize, &placement.direction),
+                    *letter,
+                )
+            })
+            .filter(|(position, _)| !self.board.is_out_of_bounds(*position))
+            .collect();
+        let Some((start_pos, _)) = in_bounds.first() else {
+            return Err(ScrabbleRuntimeError::PositionOutOfBounds(
+                placement.start_pos,
+            ));
+        };
+        let letters: Vec<ScrabbleLetter> = in_bounds.iter().map(|(_, letter)| *letter).collect();
+
+        Ok(Placement::new(&letters, start_pos, &placement.direction))
+    }
+
     fn place_on_board(&mut self, placement: &Placement) -> Result<(), ScrabbleRuntimeError> {
+        let outcome = self.preview_placement(self.current_player, placement)?;
         self.get_current_player().try_consume(&placement.letters)?;
+        let points = outcome.points_for(self.current_player);
 
-        match self.try_place(placement) {
-            Ok(_) => (),
-            Err(e) => {
-                self.get_current_player()
-                    .letter_bag
-                    .append(&mut placement.letters.clone());
-                return Err(e);
-            }
+        self.board = outcome.board;
+        for (player_id, points) in &outcome.score_changes {
+            self.players[*player_id].score = self.players[*player_id].score.saturating_add(*points);
+        }
+        if self.verbose {
+            print!(
+                "{}",
+                format_scoring_breakdown(
+                    &outcome.term_scores,
+                    &outcome.score_changes,
+                    &self.evaluation_rules(),
+                )
+            );
+        }
+        if let Some(goal) = self.goal.filter(|goal| self.has_term_value(*goal)) {
+            println!(
+                "P{} reached the goal, a term with the value {}!",
+                self.current_player + 1,
+                goal
+            );
+            self.goal = None;
+        }
+
+        self.get_current_player().moves_made += 1;
+        let mut formed_terms = outcome
+            .term_scores
+            .iter()
+            .map(|(term, _, _)| term.clone())
+            .collect();
+        self.get_current_player()
+            .played_terms
+            .append(&mut formed_terms);
+        self.history.push(MoveRecord {
+            player: self.current_player,
+            player_move_number: self.players[self.current_player].moves_made,
+            placement: placement.clone(),
+            score_changes: outcome.score_changes,
+            term_scores: outcome.term_scores,
+            points,
+            was_first_placement: self.is_first_placement,
+            reached_state: 0,
+        });
+        self.next_player();
+        self.is_first_placement = false;
+        let reached_state = self.record_state();
+        self.history
+            .last_mut()
+            .expect("BUG: the move was recorded above!")
+            .reached_state = reached_state;
+
+        Ok(())
+    }
+
+    fn preview_placement(
+        &self,
+        player_id: PlayerID,
+        placement: &Placement,
+    ) -> Result<PlacementOutcome, ScrabbleRuntimeError> {
+        if let Some(operator) = placement
+            .letters
+            .iter()
+            .find(|letter| letter.is_operator() && !self.config.allowed_operators.contains(letter))
+        {
+            return Err(ScrabbleRuntimeError::InvalidPlacement(format!(
+                "The operator '{}' is not allowed in this game!",
+                operator
+            )));
+        }
+
+        if self.is_first_placement {
+            self.check_opening_rule(placement)?;
+        }
+
+        let board = self.board_with(player_id, placement)?;
+        if self.config.require_digit {
+            board
+                .validate_board()
+                .map_err(ScrabbleRuntimeError::InvalidPlacement)?;
         }
 
-        let (terms, owners): (Vec<Term>, Vec<Owner>) = self
+        // a single letter has no main axis, so all of its terms count as main terms
+        let is_single_letter = placement.letters.len() == 1;
+        let (terms, results): (Vec<_>, Vec<_>) = board
             .get_placement_terms(placement)
             .into_iter()
-            .filter(|term| !term.0.is_singleton())
+            .enumerate()
+            .map(|(index, (term, owners))| (term, owners, index > 0 && !is_single_letter))
+            .filter(|(term, _, is_cross_term)| {
+                let is_ignored = *is_cross_term && self.config.cross_terms == CrossTermRule::Ignore;
+                !term.is_singleton() && !is_ignored
+            })
+            .map(|(term, owners, is_cross_term)| {
+                let (term, result) = self.best_reading(term);
+                ((term, owners, is_cross_term), result)
+            })
             .unzip();
-        let results = terms
-            .iter()
-            .map(|to_eval| to_eval.evaluate())
-            .collect::<Vec<Result<i32, String>>>();
         let are_terms_valid = results.iter().all(|res| res.is_ok());
-        assert!(!self.is_first_placement || terms.len() == 1);
+        assert!(!self.is_first_placement || terms.len() <= 1);
 
-        // combine these
         if !are_terms_valid {
-            self.get_current_player()
-                .letter_bag
-                .append(&mut placement.letters.clone());
-            self.revert_placement(placement);
-            return Err(ScrabbleRuntimeError::InvalidPlacement(
-                "The placement leads to invalid terms!".to_string(),
-            ));
+            let mut message = "The placement leads to invalid terms!".to_string();
+            if self.config.trace_errors {
+                for ((term, _, _), _) in terms.iter().zip(&results).filter(|(_, res)| res.is_err())
+                {
+                    message += &format!("\n{}:", term.to_rpn_string());
+                    for step in term.evaluation_trace(&self.evaluation_rules()) {
+                        message += &format!("\n  {}", step);
+                    }
+                }
+            }
+            return Err(ScrabbleRuntimeError::InvalidPlacement(message));
         }
         if terms.is_empty() {
-            self.get_current_player()
-                .letter_bag
-                .append(&mut placement.letters.clone());
-            self.revert_placement(placement);
-            return Err(ScrabbleRuntimeError::InvalidPlacement(
-                "Terms of length 1 are not allowed!".to_string(),
-            ));
+            return Err(ScrabbleRuntimeError::NoTermsFormed);
+        }
+        if let Some(max_operand) = self.config.max_operand {
+            if let Some(operand) = terms
+                .iter()
+                .filter_map(|(term, _, _)| term.max_operand(&self.evaluation_rules()))
+                .find(|operand| *operand > max_operand)
+            {
+                return Err(ScrabbleRuntimeError::InvalidPlacement(format!(
+                    "The operand {} is larger than the allowed maximum of {}!",
+                    operand, max_operand
+                )));
+            }
         }
-        // the following only makes sense with normal scrabble 
+        if self.config.unique_term_values {
+            self.check_unique_term_values(&board)?;
+        }
+        // the following only makes sense with normal scrabble
         // if !self.is_first_placement && terms.len() == 1 && terms[0] == Term::new(&placement.letters)
         // {
-        //     self.get_current_player()
-        //         .letter_bag
-        //         .append(&mut placement.letters.clone());
-        //     self.revert_placement(placement);
         //     return Err(ScrabbleRuntimeError::InvalidPlacement(
         //         "Your placement must include at least one already placed letter!".to_string(),
         //     ));
@@ -170,149 +743,887 @@ impl<const N: usize> ScrabbleGame<N> {
 
         // validity already checked -> are_terms_valid
         let results_unwrapped = results.into_iter().map(|res| res.unwrap());
+        let mut score_changes = Vec::new();
+        let mut term_scores = Vec::new();
 
-        owners
-            .into_iter()
-            .zip(results_unwrapped.into_iter())
-            .for_each(|(owner, score)| match owner {
-                Owner::None => (),
-                Owner::Owning(player_id) => self.players[player_id].score += score as isize,
-            });
+        for ((term, term_owners, is_cross_term), value) in terms.iter().zip(results_unwrapped) {
+            if *is_cross_term && self.config.cross_terms == CrossTermRule::Validate {
+                continue;
+            }
+            let score = self.term_score(term, value);
+            term_scores.push((term.clone(), value, score));
 
-        self.next_player();
-        self.is_first_placement = false;
+            for (owner, points) in
+                term.score_contributions(score, term_owners, self.config.ownership_rule)
+            {
+                if let Owner::Owning(player_id) = owner {
+                    score_changes.push((player_id, points as isize));
+                }
+            }
+        }
+        if self.config.no_repeated_terms {
+            if let Some((term, _, _)) = term_scores
+                .iter()
+                .find(|(term, _, _)| self.players[player_id].played_terms.contains(term))
+            {
+                return Err(ScrabbleRuntimeError::InvalidPlacement(format!(
+                    "P{} already formed the term {}!",
+                    player_id + 1,
+                    term.to_rpn_string()
+                )));
+            }
+        }
 
-        Ok(())
+        Ok(PlacementOutcome {
+            board,
+            score_changes,
+            term_scores,
+        })
     }
 
-    fn try_place(&mut self, placement: &Placement) -> Result<(), ScrabbleRuntimeError> {
+    fn board_with(
+        &self,
+        player_id: PlayerID,
+        placement: &Placement,
+    ) -> Result<GameBoard, ScrabbleRuntimeError> {
+        let mut board = self.board.clone();
         for offset in 0..placement.letters.len() {
-            if let Err(err) = self.board.try_place(
-                self.current_player,
-                placement.letters[offset],
-                move_position(placement.start_pos, offset as isize, &placement.direction),
-            ) {
-                self.revert_placement(&Placement::new(
-                    &placement.letters[..offset].to_vec(),
-                    &placement.start_pos,
-                    &placement.direction,
-                ));
-                return Err(err);
-            }
+            let position =
+                checked_move_position(placement.start_pos, offset as isize, &placement.direction)
+                    .ok_or(ScrabbleRuntimeError::BlockedSpace)?;
+            board.try_place(player_id, placement.letters[offset], position)?;
         }
 
-        Ok(())
+        Ok(board)
     }
 
-    fn revert_placement(&mut self, placement: &Placement) {
-        (0..placement.letters.len()).into_iter().for_each(|offset| {
-            self.board.clear(move_position(
-                placement.start_pos,
-                offset as isize,
-                &placement.direction,
-            ))
-        });
+    // (player, that player's own move number) for every move in the history
+    pub fn move_numbers(&self) -> Vec<(PlayerID, usize)> {
+        self.history
+            .iter()
+            .map(|record| (record.player, record.player_move_number))
+            .collect()
     }
 
-    fn get_placement_terms(&self, placement: &Placement) -> Vec<(Term, Owner)> {
-        let mut terms = Vec::new();
-        let orthogonal = placement.direction.orthogonal();
-
-        terms.push(self.get_term(placement.start_pos, &placement.direction));
+    pub fn explain(&self, letter: ScrabbleLetter) -> String {
+        let effect = match letter {
+            ScrabbleLetter::Plus => "an operator, it adds the two topmost numbers",
+            ScrabbleLetter::Minus => {
+                "an operator, it subtracts the topmost number from the one below it"
+            }
+            ScrabbleLetter::Dot => "an operator, it multiplies the two topmost numbers",
+            ScrabbleLetter::Slash => {
+                "an operator, it divides the number below the topmost one by the topmost number"
+            }
+            ScrabbleLetter::Percent => {
+                "an operator, it takes the number below the topmost one modulo the topmost number"
+            }
+            ScrabbleLetter::Caret => {
+                "an operator, it raises the number below the topmost one to the power of the topmost number"
+            }
+            ScrabbleLetter::OperatorJoker => {
+                "an operator joker, it acts as whichever of + - * / % ^ gives the term the highest value"
+            }
+            ScrabbleLetter::LeftParenthesis | ScrabbleLetter::RightParenthesis => {
+                "a parenthesis, it groups parts of infix terms"
+            }
+            ScrabbleLetter::Equals => "an equals sign, both sides of it have to have the same value",
+            ScrabbleLetter::Empty => "an empty cell",
+            _ => "a digit, it pushes its number onto the stack",
+        };
+        let mut explanation = format!("'{}' is {}.", letter, effect);
 
-        for offset in 0..placement.letters.len() as isize {
-            terms.push(self.get_term(
-                move_position(placement.start_pos, offset, &placement.direction),
-                &orthogonal,
-            ));
+        if letter.is_operator() && !self.config.allowed_operators.contains(&letter) {
+            explanation += " It is not allowed in this game.";
+        }
+        if letter.is_digit() && self.config.scoring_mode == ScoringMode::TileSum {
+            explanation += &format!(" It is worth {} points.", letter as i32);
         }
 
-        terms
+        explanation
     }
 
-    fn collect_to_term_end(
-        &self,
-        position: Position,
-        direction: &Direction,
-        iter_dir: TermDirection,
-    ) -> Vec<Position> {
-        let mut curr_iter_offset = 0;
-
-        std::iter::from_fn(move || {
-            let curr_pos = move_position(position, curr_iter_offset, &direction);
+    pub fn has_term_value(&self, value: i64) -> bool {
+        self.board
+            .all_terms()
+            .into_iter()
+            .filter(|(term, _, _)| !term.is_singleton())
+            .any(|(term, _, _)| self.best_reading(term).1 == Ok(value))
+    }
 
-            if self.board.is_out_of_bounds(curr_pos) || self.board.is_empty(curr_pos) {
-                None
-            } else {
-                curr_iter_offset += iter_dir as isize;
-                Some(curr_pos)
-            }
-        })
-        .into_iter()
-        .collect()
+    // the term that scored the most in a single move, together with the player who made the move
+    pub fn highest_scoring_term(&self) -> Option<(PlayerID, Term, i64)> {
+        self.history
+            .iter()
+            .flat_map(|record| {
+                record
+                    .term_scores
+                    .iter()
+                    .map(move |(term, _, score)| (record.player, term, *score))
+            })
+            .min_by_key(|(_, _, score)| std::cmp::Reverse(*score))
+            .map(|(player_id, term, score)| (player_id, term.clone(), score))
     }
 
-    fn get_term(&self, position: Position, direction: &Direction) -> (Term, Owner) {
-        let start_sequence =
-            self.collect_to_term_end(position, direction, TermDirection::Decreasing);
-        let end_sequence = self.collect_to_term_end(position, direction, TermDirection::Increasing);
-        let term_sequence = start_sequence
+    // every tile of the game, whether it is held by a player, in the pile or on the board
+    pub fn tile_census(&self) -> HashMap<ScrabbleLetter, usize> {
+        let mut census = HashMap::new();
+        let held_tiles = self
+            .players
+            .iter()
+            .flat_map(|player| player.letter_bag.iter().copied());
+        let board_tiles = self
+            .board
+            .occupied_cells()
             .into_iter()
-            .rev()
-            .chain(end_sequence.into_iter().skip(1));
+            .map(|(_, cell)| cell.letter);
 
-        let (term, owners): (Vec<ScrabbleLetter>, Vec<Owner>) = term_sequence
-            .map(|pos| self.board.try_get(pos))
-            .collect::<Result<Vec<(ScrabbleLetter, Owner)>, ScrabbleRuntimeError>>()
-            .expect("BUG: term is out of bounds!")
-            .into_iter()
-            .unzip();
+        for letter in held_tiles
+            .chain(self.pile.iter().copied())
+            .chain(board_tiles)
+        {
+            *census.entry(letter).or_insert(0) += 1;
+        }
 
-        let mut frequencies = frequency(&owners);
-        frequencies.sort_by(|a, b| b.1.cmp(&a.1));
-        assert!(frequencies.len() > 0);
+        census
+    }
 
-        if frequencies.len() == 1 {
-            (Term::new(&term), frequencies[0].0)
-        } else {
-            assert!(frequencies.len() >= 2);
-            let owner = if frequencies[0].1 == frequencies[1].1 {
-                Owner::None
-            } else {
-                frequencies[0].0
-            };
-            (Term::new(&term), owner)
+    // the whole score counts, including the points a player got from the moves of others
+    pub fn points_per_move(&self) -> Vec<f64> {
+        self.players
+            .iter()
+            .map(|player| {
+                if player.moves_made == 0 {
+                    0.0
+                } else {
+                    player.score as f64 / player.moves_made as f64
+                }
+            })
+            .collect()
+    }
+
+    fn print_stats(&self) {
+        println!("moves: {}", self.history.len());
+        for ((player_id, player), average) in
+            self.players.iter().enumerate().zip(self.points_per_move())
+        {
+            println!(
+                "P{}: {:.1} points per move, {} tiles left",
+                player_id + 1,
+                average,
+                player.letter_bag.len()
+            );
         }
+        if let Some((player_id, term, score)) = self.highest_scoring_term() {
+            println!(
+                "best term: {} = {} points by P{}",
+                term.to_rpn_string(),
+                score,
+                player_id + 1
+            );
+        }
+        println!("tiles in the pile: {}", self.pile_remaining());
     }
 
-    fn get_current_player(&mut self) -> &mut Player {
-        &mut self.players[self.current_player]
+    // points the player is behind the leader, 0 for the leader
+    pub fn gap(&self, player_id: PlayerID) -> Result<isize, ScrabbleRuntimeError> {
+        let player = self
+            .players
+            .get(player_id)
+            .ok_or(ScrabbleRuntimeError::PlayerIDOutOfBOunds(player_id))?;
+        let leading_score = self.players.iter().map(|player| player.score).max();
+
+        Ok(leading_score.unwrap_or(0) - player.score)
     }
 
-    fn next_player(&mut self) {
-        self.current_player = (self.current_player + 1) % self.players.len();
+    pub fn current_player(&self) -> PlayerID {
+        self.current_player
     }
-}
 
-pub struct GameBoard<const N: usize> {
-    tiles: [[(ScrabbleLetter, Owner); N]; N],
-}
+    pub fn current_and_next_player(&self) -> (PlayerID, PlayerID) {
+        (
+            self.current_player,
+            (self.current_player + 1) % self.players.len(),
+        )
+    }
 
-impl<const N: usize> GameBoard<N> {
-    pub fn try_place(
-        &mut self,
-        placer_id: PlayerID,
-        to_place: ScrabbleLetter,
-        pos: Position,
-    ) -> Result<(), ScrabbleRuntimeError> {
+    pub fn symbol_table(&self, player_id: PlayerID) -> SymbolTable {
+        self.config
+            .symbol_tables
+            .get(&player_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn scores(&self) -> Vec<isize> {
+        self.players.iter().map(|player| player.score).collect()
+    }
+
+    pub fn legal_moves(&self, player_id: PlayerID) -> Vec<Placement> {
+        self.candidate_placements(player_id)
+            .filter(|placement| self.preview_placement(player_id, placement).is_ok())
+            .collect()
+    }
+
+    // legal moves after which the game is over and won by the player
+    pub fn winning_moves(&self, player_id: PlayerID) -> Vec<Placement> {
+        self.legal_moves(player_id)
+            .into_iter()
+            .filter(|placement| {
+                let mut game = self.clone();
+                game.verbose = false;
+                game.goal = None;
+                game.current_player = player_id;
+                game.place_on_board(placement).is_ok() && game.winner() == Some(player_id)
+            })
+            .collect()
+    }
+
+    // fewest tiles that form a term of the given value on an empty board, empty if there are none
+    pub fn min_rack_for(&self, target: i64) -> Vec<ScrabbleLetter> {
+        let tile_kinds = "0123456789"
+            .chars()
+            .filter_map(ScrabbleLetter::from_char)
+            .chain(self.config.allowed_operators.iter().copied());
+        let pool: Vec<ScrabbleLetter> = tile_kinds
+            .flat_map(|letter| std::iter::repeat_n(letter, MAX_PLACEMENT_LENGTH))
+            .collect();
+
+        let rules = self.evaluation_rules();
+        letter_sequences(&pool, MAX_PLACEMENT_LENGTH)
+            .into_iter()
+            .filter(|letters| {
+                letters.len() > 1 && Term::new(letters).evaluate_with(&rules) == Ok(target)
+            })
+            .min_by_key(|letters| letters.len())
+            .map(|mut letters| {
+                letters.sort();
+                letters
+            })
+            .unwrap_or_default()
+    }
+
+    // sorted distinct values of the terms a single placement from the rack can form on its own
+    pub fn achievable_values(&self, rack: &[ScrabbleLetter]) -> Vec<i64> {
+        let rules = self.evaluation_rules();
+        let mut values: Vec<i64> = letter_sequences(rack, MAX_PLACEMENT_LENGTH)
+            .into_iter()
+            .filter(|letters| {
+                letters.len() > 1
+                    && letters.iter().all(|letter| {
+                        !letter.is_operator() || self.config.allowed_operators.contains(letter)
+                    })
+            })
+            .filter_map(|letters| Term::new(&letters).evaluate_with(&rules).ok())
+            .collect();
+        values.sort();
+        values.dedup();
+
+        values
+    }
+
+    // summed points every affected player would get if the current player made the placement
+    pub fn score_deltas(
+        &self,
+        placement: &Placement,
+    ) -> Result<Vec<(PlayerID, isize)>, ScrabbleRuntimeError> {
+        let outcome = self.preview_placement(self.current_player, placement)?;
+        let mut deltas: Vec<(PlayerID, isize)> = Vec::new();
+
+        for (player_id, _) in &outcome.score_changes {
+            if !deltas.iter().any(|(receiver, _)| receiver == player_id) {
+                deltas.push((*player_id, outcome.points_for(*player_id)));
+            }
+        }
+        deltas.sort();
+
+        Ok(deltas)
+    }
+
+    // highest scoring legal move of the player to move next, the first one found wins ties
+    pub fn best_response(&self) -> Option<(PlayerID, Placement, isize)> {
+        let player_id = self.current_player;
+
+        self.candidate_placements(player_id)
+            .filter_map(|placement| {
+                self.preview_placement(player_id, &placement)
+                    .ok()
+                    .map(|outcome| (placement, outcome.points_for(player_id)))
+            })
+            .min_by_key(|(_, points)| std::cmp::Reverse(*points))
+            .map(|(placement, points)| (player_id, placement, points))
+    }
+
+    // only placements with all of their tiles inside the rectangle, both corners included
+    pub fn best_move_in_region(
+        &self,
+        player_id: PlayerID,
+        top_left: Position,
+        bottom_right: Position,
+    ) -> Option<(Placement, isize)> {
+        let is_inside = |position: Position| {
+            (top_left.0..=bottom_right.0).contains(&position.0)
+                && (top_left.1..=bottom_right.1).contains(&position.1)
+        };
+
+        self.candidate_placements(player_id)
+            .filter(|placement| {
+                (0..placement.letters.len() as isize).all(|offset| {
+                    is_inside(move_position(
+                        placement.start_pos,
+                        offset,
+                        &placement.direction,
+                    ))
+                })
+            })
+            .filter_map(|placement| {
+                self.preview_placement(player_id, &placement)
+                    .ok()
+                    .map(|outcome| (placement, outcome.points_for(player_id)))
+            })
+            .min_by_key(|(_, points)| std::cmp::Reverse(*points))
+    }
+
+    // only one placement is allowed per turn, so this is the score of the best response
+    pub fn potential(&self) -> isize {
+        self.best_response()
+            .map(|(_, _, points)| points)
+            .unwrap_or(0)
+    }
+
+    // players can still exchange their racks while tiles are left, the move search only runs after that
+    pub fn is_stuck(&self) -> bool {
+        self.pile.is_empty() && self.players_able_to_move().is_empty()
+    }
+
+    pub fn players_able_to_move(&self) -> Vec<PlayerID> {
+        (0..self.players.len())
+            .filter(|player_id| self.has_legal_move(*player_id))
+            .collect()
+    }
+
+    // stops at the first legal move instead of collecting all of them like legal_moves
+    fn has_legal_move(&self, player_id: PlayerID) -> bool {
+        self.candidate_placements(player_id)
+            .any(|placement| self.preview_placement(player_id, &placement).is_ok())
+    }
+
+    // an unknown player has no tiles and therefore no candidates
+    fn candidate_placements(&self, player_id: PlayerID) -> impl Iterator<Item = Placement> + '_ {
+        let rack = self
+            .players
+            .get(player_id)
+            .map_or(&[][..], |player| &player.letter_bag);
+        let sequences = letter_sequences(rack, MAX_PLACEMENT_LENGTH);
+        let size = self.config.board_size as isize;
+
+        sequences.into_iter().flat_map(move |letters| {
+            // a single letter forms the same terms in both directions
+            let directions = if letters.len() == 1 {
+                vec![Direction::Horizontal]
+            } else {
+                vec![Direction::Horizontal, Direction::Vertical]
+            };
+
+            (0..size)
+                .flat_map(move |y| (0..size).map(move |x| (x, y)))
+                .filter(|position| self.board.is_empty(*position))
+                .flat_map(move |position| {
+                    let letters = letters.clone();
+                    directions
+                        .clone()
+                        .into_iter()
+                        .map(move |direction| Placement::new(&letters, &position, &direction))
+                })
+        })
+    }
+
+    pub fn drops(&self, letter: ScrabbleLetter) -> Vec<(Position, isize)> {
+        let mut drops = Vec::new();
+
+        for y in 0..self.config.board_size as isize {
+            for x in 0..self.config.board_size as isize {
+                let placement = Placement::new(&[letter], &(x, y), &Direction::Horizontal);
+                if let Ok(outcome) = self.preview_placement(self.current_player, &placement) {
+                    drops.push(((x, y), outcome.points_for(self.current_player)));
+                }
+            }
+        }
+
+        drops
+    }
+
+    fn undo(&mut self) -> Result<(), ScrabbleRuntimeError> {
+        let placement = &self
+            .history
+            .last()
+            .ok_or(ScrabbleRuntimeError::NothingToUndo)?
+            .placement;
+        // locked tiles stay on the board, so the move can't be taken back anymore
+        if let Some(position) = (0..placement.letters.len() as isize)
+            .map(|offset| move_position(placement.start_pos, offset, &placement.direction))
+            .find(|position| self.board.is_locked(*position))
+        {
+            return Err(ScrabbleRuntimeError::LockedCell(position));
+        }
+        let record = self.history.pop().expect("BUG: history checked above!");
+        // passes may have followed the move, so the current state isn't necessarily the one it reached
+        if let Some(count) = self.seen_states.get_mut(&record.reached_state) {
+            *count -= 1;
+        }
+
+        self.revert_placement(&record.placement);
+        for (player_id, points) in record.score_changes {
+            self.players[player_id].score -= points;
+        }
+        self.current_player = record.player;
+        self.get_current_player().moves_made -= 1;
+        let played_count = self.get_current_player().played_terms.len();
+        self.get_current_player()
+            .played_terms
+            .truncate(played_count - record.term_scores.len());
+        self.get_current_player()
+            .letter_bag
+            .append(&mut record.placement.letters.clone());
+        self.is_first_placement = record.was_first_placement;
+
+        Ok(())
+    }
+
+    pub fn owned_cells(&self) -> Vec<usize> {
+        let occupied_cells = self.board.occupied_cells();
+
+        (0..self.players.len())
+            .map(|player_id| {
+                occupied_cells
+                    .iter()
+                    .filter(|(_, cell)| cell.owner == Owner::Owning(player_id))
+                    .count()
+            })
+            .collect()
+    }
+
+    pub fn is_board_full(&self) -> bool {
+        self.board.empty_count() == 0
+    }
+
+    pub fn is_drawn_by_repetition(&self) -> bool {
+        self.config
+            .repetition_limit
+            .is_some_and(|limit| self.seen_states.values().any(|count| *count >= limit))
+    }
+
+    // same for equal positions, racks are hashed regardless of their tile order
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.board.hash(&mut hasher);
+        for player in &self.players {
+            player.score.hash(&mut hasher);
+            let mut rack = player.letter_bag.clone();
+            rack.sort();
+            rack.hash(&mut hasher);
+        }
+        self.current_player.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // None while the game is running or if it ended in a draw
+    pub fn winner(&self) -> Option<PlayerID> {
+        let is_territory_game = self.config.victory_rule == VictoryRule::Territory;
+        if self.is_drawn_by_repetition() {
+            return None;
+        }
+        let is_over = (is_territory_game && self.is_board_full()) || self.is_stuck();
+        if !is_over {
+            return None;
+        }
+        if is_territory_game {
+            return self.territory_winner();
+        }
+        let scores = self.scores();
+        let best_score = *scores.iter().max()?;
+        let mut leaders = (0..scores.len()).filter(|player_id| scores[*player_id] == best_score);
+
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader),
+            _ => None,
+        }
+    }
+
+    // None if the lead is shared
+    pub fn territory_winner(&self) -> Option<PlayerID> {
+        let owned_cells = self.owned_cells();
+        let most_cells = *owned_cells.iter().max()?;
+        let mut leaders =
+            (0..owned_cells.len()).filter(|player_id| owned_cells[*player_id] == most_cells);
+
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader),
+            _ => None,
+        }
+    }
+
+    fn print_territory(&self) {
+        for (owner, share) in self.territory_shares() {
+            match owner {
+                Owner::Owning(player_id) => println!("P{}: {:.1}%", player_id + 1, share),
+                Owner::None if share > 0.0 => println!("neutral: {:.1}%", share),
+                Owner::None => {}
+            }
+        }
+    }
+
+    // percentage of the occupied cells owned by every player, followed by the neutral share
+    pub fn territory_shares(&self) -> Vec<(Owner, f64)> {
+        let occupied_cells = self.board.occupied_cells();
+        let cell_share = |owner: Owner| {
+            let owned_count = occupied_cells
+                .iter()
+                .filter(|(_, cell)| cell.owner == owner)
+                .count();
+            if occupied_cells.is_empty() {
+                0.0
+            } else {
+                owned_count as f64 * 100.0 / occupied_cells.len() as f64
+            }
+        };
+
+        (0..self.players.len())
+            .map(Owner::Owning)
+            .chain([Owner::None])
+            .map(|owner| (owner, cell_share(owner)))
+            .collect()
+    }
+
+    fn deal(&mut self, count: usize) -> Result<(), ScrabbleRuntimeError> {
+        if let Some(rack_size) = self.config.rack_size.filter(|rack_size| count > *rack_size) {
+            return Err(ScrabbleRuntimeError::RackSizeExceeded(rack_size));
+        }
+
+        // the current rack goes back to the pile before the new one is drawn
+        let available = self.pile.len() + self.players[self.current_player].letter_bag.len();
+        if count > available {
+            return Err(ScrabbleRuntimeError::NotEnoughTiles(available));
+        }
+        let mut returned_letters = std::mem::take(&mut self.get_current_player().letter_bag);
+        self.pile.append(&mut returned_letters);
+        self.get_current_player().letter_bag = self.draw(count);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, count: usize) -> Vec<ScrabbleLetter> {
+        (0..count)
+            .map(|_| {
+                let index = self.rng.below(self.pile.len());
+                self.pile.swap_remove(index)
+            })
+            .collect()
+    }
+
+    pub fn pile_remaining(&self) -> usize {
+        self.pile.len()
+    }
+
+    // average value of the digits left in the pile, 0 if there are none
+    pub fn expected_draw_value(&self) -> f64 {
+        let digits: Vec<i32> = self
+            .pile
+            .iter()
+            .filter(|letter| letter.is_digit())
+            .map(|digit| *digit as i32)
+            .collect();
+
+        if digits.is_empty() {
+            0.0
+        } else {
+            digits.iter().sum::<i32>() as f64 / digits.len() as f64
+        }
+    }
+
+    // one line per move like "P1 12+;1;1;H +3", the placements are absolute and replay_moves reads them back
+    pub fn move_notation(&self) -> String {
+        self.history
+            .iter()
+            .map(|record| {
+                format!(
+                    "P{} {} {:+}\n",
+                    record.player + 1,
+                    record.placement,
+                    record.points
+                )
+            })
+            .collect()
+    }
+
+    // plays the moves of move_notation, the game has to start with the racks the moves were made from
+    pub fn replay_moves(&mut self, notation: &str) -> Result<(), ScrabbleRuntimeError> {
+        if !self.history.is_empty() {
+            return Err(ScrabbleRuntimeError::GameAlreadyStarted);
+        }
+
+        // the moves are played on a copy, so a failing line leaves the game as it was
+        let mut replayed = self.clone();
+        for (index, line) in notation.lines().enumerate() {
+            let invalid_line_err = |cause: String| ScrabbleRuntimeError::InvalidMoveNotation {
+                line: index + 1,
+                cause,
+            };
+            let [player_str, placement_str, points_str] =
+                line.split(' ').collect::<Vec<&str>>()[..]
+            else {
+                return Err(invalid_line_err(
+                    "A move needs a player, a placement and its points!".to_string(),
+                ));
+            };
+            let player_id = player_str
+                .strip_prefix('P')
+                .and_then(|id| id.parse::<PlayerID>().ok())
+                .filter(|id| (1..=replayed.players.len()).contains(id))
+                .ok_or_else(|| invalid_line_err(format!("'{}' is not a player!", player_str)))?
+                - 1;
+            let Ok(Command::Place(placement)) = format!("place {}", placement_str).parse() else {
+                return Err(invalid_line_err(format!(
+                    "'{}' is not a valid placement!",
+                    placement_str
+                )));
+            };
+            let points: isize = points_str
+                .parse()
+                .map_err(|_| invalid_line_err(format!("'{}' are not valid points!", points_str)))?;
+
+            // passes aren't part of the notation, they are made up for the skipped players
+            while replayed.current_player != player_id {
+                replayed.next_player();
+                replayed.record_state();
+            }
+            replayed.place_on_board(&placement).map_err(|err| {
+                invalid_line_err(err.to_string().trim_start_matches("Error: ").to_string())
+            })?;
+            let scored_points = replayed
+                .history
+                .last()
+                .expect("BUG: the move was placed above!")
+                .points;
+            if scored_points != points {
+                return Err(invalid_line_err(format!(
+                    "The move scored {:+} instead of {:+}!",
+                    scored_points, points
+                )));
+            }
+        }
+        *self = replayed;
+
+        Ok(())
+    }
+
+    // replaces the board, the imported tiles belong to nobody and the history starts over
+    fn import_csv(&mut self, path: &str) -> Result<(), ScrabbleRuntimeError> {
+        let csv = std::fs::read_to_string(path).map_err(|err| ScrabbleRuntimeError::FileError {
+            path: path.to_string(),
+            cause: err.to_string(),
+        })?;
+        let board = GameBoard::from_csv(&csv, self.config.board_size)?;
+        let rules = self.evaluation_rules();
+        for (position, cell) in board.occupied_cells() {
+            rules.check_letter(&cell.letter).map_err(|cause| {
+                ScrabbleRuntimeError::InvalidCsv(format!("{:?}: {}", position, cause))
+            })?;
+        }
+
+        self.is_first_placement = board.occupied_cells().is_empty();
+        self.board = board;
+        self.history.clear();
+        // the scores belong to the replaced board
+        for player in &mut self.players {
+            player.score = 0;
+            player.moves_made = 0;
+            player.played_terms.clear();
+        }
+        self.seen_states.clear();
+        self.record_state();
+
+        Ok(())
+    }
+
+    fn check_opening_rule(&self, placement: &Placement) -> Result<(), ScrabbleRuntimeError> {
+        match self.config.opening_rule {
+            OpeningRule::Free => Ok(()),
+            OpeningRule::CenterOperator => {
+                let center = self.board.center();
+                let center_letter = (0..placement.letters.len())
+                    .find(|offset| {
+                        checked_move_position(
+                            placement.start_pos,
+                            *offset as isize,
+                            &placement.direction,
+                        ) == Some(center)
+                    })
+                    .map(|offset| placement.letters[offset]);
+
+                match center_letter {
+                    Some(letter) if letter.is_operator() => Ok(()),
+                    _ => Err(ScrabbleRuntimeError::InvalidPlacement(format!(
+                        "The opening placement has to put an operator on the center {:?}!",
+                        center
+                    ))),
+                }
+            }
+        }
+    }
+
+    pub fn terms_for(&self, placement: &Placement) -> Result<Vec<Term>, ScrabbleRuntimeError> {
+        Ok(self
+            .board_with(self.current_player, placement)?
+            .get_placement_terms(placement)
+            .into_iter()
+            .map(|(term, _)| term)
+            .filter(|term| !term.is_singleton())
+            .collect())
+    }
+
+    pub fn longest_term(&self) -> Option<(Term, i64, Position)> {
+        self.board
+            .all_terms()
+            .into_iter()
+            .filter_map(|(term, position, _)| {
+                term.evaluate_with(&self.evaluation_rules())
+                    .ok()
+                    .map(|value| (term, value, position))
+            })
+            // prefer the smaller position among terms of the same length
+            .max_by(|a, b| a.0.len().cmp(&b.0.len()).then(b.2.cmp(&a.2)))
+    }
+
+    fn check_unique_term_values(&self, board: &GameBoard) -> Result<(), ScrabbleRuntimeError> {
+        let mut values = Vec::new();
+
+        for (term, _, _) in board.all_terms() {
+            if term.is_singleton() {
+                continue;
+            }
+            let Ok(value) = self.best_reading(term).1 else {
+                continue;
+            };
+            if values.contains(&value) {
+                return Err(ScrabbleRuntimeError::InvalidPlacement(format!(
+                    "A term with the value {} is already on the board!",
+                    value
+                )));
+            }
+            values.push(value);
+        }
+
+        Ok(())
+    }
+
+    // the reversed reading is only used if reversible terms are enabled and it is valid and scores better
+    fn best_reading(&self, term: Term) -> (Term, Result<i64, String>) {
+        let result = term.evaluate_with(&self.evaluation_rules());
+        if !self.config.reversible_terms {
+            return (term, result);
+        }
+        let reversed = term.reversed();
+        let reversed_result = reversed.evaluate_with(&self.evaluation_rules());
+
+        match (&result, &reversed_result) {
+            (Err(_), Ok(_)) => (reversed, reversed_result),
+            (Ok(value), Ok(reversed_value))
+                if self.term_score(&reversed, *reversed_value) > self.term_score(&term, *value) =>
+            {
+                (reversed, reversed_result)
+            }
+            _ => (term, result),
+        }
+    }
+
+    fn term_score(&self, term: &Term, value: i64) -> i64 {
+        let score = match self.config.scoring_mode {
+            ScoringMode::TermValue => value,
+            ScoringMode::TileSum => term.tile_sum() as i64,
+        };
+        let zero_count = term.count(ScrabbleLetter::Num0) as i64;
+
+        match self.config.zero_rule {
+            ZeroRule::Regular => score,
+            ZeroRule::VoidsTerm if zero_count > 0 => 0,
+            ZeroRule::VoidsTerm => score,
+            ZeroRule::Penalty => score.saturating_sub(zero_count * self.config.zero_penalty as i64),
+        }
+    }
+
+    fn revert_placement(&mut self, placement: &Placement) {
+        (0..placement.letters.len()).for_each(|offset| {
+            self.board.clear(move_position(
+                placement.start_pos,
+                offset as isize,
+                &placement.direction,
+            ))
+        });
+    }
+
+    fn get_current_player(&mut self) -> &mut Player {
+        &mut self.players[self.current_player]
+    }
+
+    fn evaluation_rules(&self) -> EvaluationRules {
+        EvaluationRules {
+            fold_digits: self.config.fold_digits,
+            notation: self.config.notation,
+            implicit_multiplication: self.config.implicit_multiplication,
+            equations: self.config.equations,
+            allowed_operators: Some(self.config.allowed_operators.clone()),
+        }
+    }
+
+    fn record_state(&mut self) -> u64 {
+        let state_hash = self.state_hash();
+        *self.seen_states.entry(state_hash).or_insert(0) += 1;
+        state_hash
+    }
+
+    fn next_player(&mut self) {
+        (_, self.current_player) = self.current_and_next_player();
+    }
+}
+
+#[derive(Clone, Hash)]
+pub struct GameBoard {
+    size: usize,
+    tiles: Vec<Vec<Cell>>,
+}
+
+impl GameBoard {
+    pub fn try_place(
+        &mut self,
+        placer_id: PlayerID,
+        to_place: ScrabbleLetter,
+        pos: Position,
+    ) -> Result<(), ScrabbleRuntimeError> {
         if !self.is_empty(pos) {
             return Err(ScrabbleRuntimeError::BlockedSpace);
         }
-        self.tiles[pos.0 as usize][pos.1 as usize] = (to_place, Owner::Owning(placer_id));
+        self.tiles[pos.0 as usize][pos.1 as usize] = Cell {
+            letter: to_place,
+            owner: Owner::Owning(placer_id),
+            locked: false,
+        };
+        Ok(())
+    }
+
+    pub fn lock(&mut self, pos: Position) -> Result<(), ScrabbleRuntimeError> {
+        if self.try_get(pos)?.letter == ScrabbleLetter::Empty {
+            return Err(ScrabbleRuntimeError::EmptyCell(pos));
+        }
+        self.tiles[pos.0 as usize][pos.1 as usize].locked = true;
         Ok(())
     }
 
-    pub fn try_get(&self, pos: Position) -> Result<(ScrabbleLetter, Owner), ScrabbleRuntimeError> {
+    pub fn is_locked(&self, pos: Position) -> bool {
+        self.try_get(pos).is_ok_and(|cell| cell.locked)
+    }
+
+    pub fn try_get(&self, pos: Position) -> Result<Cell, ScrabbleRuntimeError> {
         if self.is_out_of_bounds(pos) {
             Err(ScrabbleRuntimeError::PositionOutOfBounds(pos))
         } else {
@@ -320,92 +1631,2208 @@ impl<const N: usize> GameBoard<N> {
         }
     }
 
+    // locked tiles are kept
     pub fn clear(&mut self, pos: Position) {
-        if self.is_out_of_bounds(pos) {
+        if self.is_out_of_bounds(pos) || self.is_locked(pos) {
             return;
         }
-        self.tiles[pos.0 as usize][pos.1 as usize] = (ScrabbleLetter::Empty, Owner::None);
+        self.tiles[pos.0 as usize][pos.1 as usize] = Cell::empty();
     }
 
     pub fn is_empty(&self, pos: Position) -> bool {
         if self.is_out_of_bounds(pos) {
             return false;
         }
-        self.tiles[pos.0 as usize][pos.1 as usize].0 == ScrabbleLetter::Empty
+        self.tiles[pos.0 as usize][pos.1 as usize].letter == ScrabbleLetter::Empty
     }
 
-    pub fn new() -> GameBoard<N> {
+    pub fn new(size: usize) -> GameBoard {
         GameBoard {
-            tiles: [[(ScrabbleLetter::Empty, Owner::None); N]; N],
+            size,
+            tiles: vec![vec![Cell::empty(); size]; size],
         }
     }
 
-    pub fn is_out_of_bounds(&self, pos: Position) -> bool {
-        pos.0 < 0 || pos.1 < 0 || pos.0 as usize >= N || pos.1 as usize >= N
-    }
-}
+    // the size is checked against the header before anything is allocated
+    pub fn from_csv(csv: &str, size: usize) -> Result<GameBoard, ScrabbleRuntimeError> {
+        let mut lines = csv.lines();
+        let header_size = lines
+            .next()
+            .ok_or(ScrabbleRuntimeError::InvalidCsv(
+                "The header row is missing!".to_string(),
+            ))?
+            .split(',')
+            .count();
+        if header_size != size {
+            return Err(ScrabbleRuntimeError::InvalidCsv(format!(
+                "The board has to be {0}x{0}, but the csv is {1}x{1}!",
+                size, header_size
+            )));
+        }
+        let rows: Vec<&str> = lines.collect();
+        let mut board = GameBoard::new(size);
+        let dimension_err = || {
+            ScrabbleRuntimeError::InvalidCsv(format!(
+                "The grid has to be {0}x{0} like the header!",
+                size
+            ))
+        };
+        if rows.len() != size {
+            return Err(dimension_err());
+        }
 
-impl<const N: usize> std::fmt::Display for GameBoard<N> {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..N {
-            for x in 0..N {
-                write!(formatter, "[{}]", &self.tiles[x][y].0)?;
+        for (y, line) in rows.into_iter().enumerate() {
+            let cells: Vec<&str> = line.split(',').collect();
+            if cells.len() != size {
+                return Err(dimension_err());
+            }
+
+            for (x, cell) in cells.into_iter().enumerate() {
+                if cell.is_empty() {
+                    continue;
+                }
+                let mut letters = cell.chars().map(ScrabbleLetter::from_char);
+                let (Some(Some(letter)), None) = (letters.next(), letters.next()) else {
+                    return Err(ScrabbleRuntimeError::InvalidCsv(format!(
+                        "'{}' is not a valid letter!",
+                        cell
+                    )));
+                };
+                board.tiles[x][y] = Cell {
+                    letter,
+                    ..Cell::empty()
+                };
             }
-            writeln!(formatter, "")?;
         }
 
-        Ok(())
+        Ok(board)
     }
-}
 
-pub struct Player {
-    letter_bag: Vec<ScrabbleLetter>,
-    score: isize,
-}
+    // a header row with the column indices followed by one row per board row, empty cells stay blank
+    pub fn to_csv(&self) -> String {
+        let mut csv = (0..self.size)
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        csv += "\n";
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
-pub enum Owner {
-    None,
-    Owning(PlayerID),
-}
+        for y in 0..self.size {
+            csv += &(0..self.size)
+                .map(|x| match self.tiles[x][y].letter {
+                    ScrabbleLetter::Empty => String::new(),
+                    letter => letter.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(",");
+            csv += "\n";
+        }
 
-impl Player {
-    pub fn new(letter_bag: &Vec<ScrabbleLetter>) -> Player {
-        Player {
-            letter_bag: letter_bag.clone(),
-            score: 0,
+        csv
+    }
+
+    // clockwise, the cells keep their owners and locks
+    pub fn rotated(&self) -> GameBoard {
+        let mut rotated = GameBoard::new(self.size);
+        for x in 0..self.size {
+            for y in 0..self.size {
+                rotated.tiles[self.size - 1 - y][x] = self.tiles[x][y];
+            }
         }
+
+        rotated
     }
 
-    pub fn try_consume(
-        &mut self,
-        to_consume: &Vec<ScrabbleLetter>,
-    ) -> Result<(), ScrabbleRuntimeError> {
-        let mut modified_letter_bag = self.letter_bag.clone();
+    // for even sizes this is the lower right of the four middle cells
+    pub fn center(&self) -> Position {
+        ((self.size / 2) as isize, (self.size / 2) as isize)
+    }
 
-        for letter in to_consume {
-            if let Some(position) = modified_letter_bag.iter().position(|val| val == letter) {
-                modified_letter_bag.remove(position);
-            } else {
-                return Err(ScrabbleRuntimeError::MissingLetters);
+    pub fn is_out_of_bounds(&self, pos: Position) -> bool {
+        pos.0 < 0 || pos.1 < 0 || pos.0 as usize >= self.size || pos.1 as usize >= self.size
+    }
+
+    pub fn empty_count(&self) -> usize {
+        self.size * self.size - self.occupied_cells().len()
+    }
+
+    pub fn occupied_cells(&self) -> Vec<(Position, Cell)> {
+        (0..self.size as isize)
+            .flat_map(|y| (0..self.size as isize).map(move |x| (x, y)))
+            .filter(|position| !self.is_empty(*position))
+            .map(|position| {
+                (
+                    position,
+                    self.tiles[position.0 as usize][position.1 as usize],
+                )
+            })
+            .collect()
+    }
+
+    // (position, cell here, cell there) for every differing cell, cells outside a board count as empty
+    pub fn diff(&self, other: &GameBoard) -> Vec<(Position, Cell, Cell)> {
+        let size = self.size.max(other.size) as isize;
+        let mut differences = Vec::new();
+
+        for y in 0..size {
+            for x in 0..size {
+                let own_cell = self.try_get((x, y)).unwrap_or(Cell::empty());
+                let other_cell = other.try_get((x, y)).unwrap_or(Cell::empty());
+                if own_cell != other_cell {
+                    differences.push(((x, y), own_cell, other_cell));
+                }
             }
         }
 
-        self.letter_bag = modified_letter_bag;
-
-        Ok(())
+        differences
     }
-}
 
-fn frequency<T: Eq + Hash + Copy>(elements: &Vec<T>) -> Vec<(T, usize)> {
-    let mut occurences = HashMap::new();
+    // (start, direction, length) of every maximal run of empty cells along the rows and columns
+    pub fn empty_runs(&self) -> Vec<(Position, Direction, usize)> {
+        let mut runs = Vec::new();
 
-    for element in elements {
-        occurences
-            .entry(*element)
-            .and_modify(|counter| *counter += 1)
-            .or_insert(1);
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            for line in 0..self.size as isize {
+                let mut run_start: Option<Position> = None;
+                // the cell past the edge counts as not empty and closes the last run
+                for along in 0..=self.size as isize {
+                    let position = match direction {
+                        Direction::Horizontal => (along, line),
+                        Direction::Vertical => (line, along),
+                    };
+                    match run_start {
+                        None if self.is_empty(position) => run_start = Some(position),
+                        Some(start) if !self.is_empty(position) => {
+                            let length = match direction {
+                                Direction::Horizontal => position.0 - start.0,
+                                Direction::Vertical => position.1 - start.1,
+                            };
+                            runs.push((start, direction.clone(), length as usize));
+                            run_start = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        runs
+    }
+
+    pub fn bounding_box(&self) -> Option<(Position, Position)> {
+        let occupied_positions: Vec<Position> = self
+            .occupied_cells()
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect();
+
+        Some((
+            (
+                occupied_positions.iter().map(|pos| pos.0).min()?,
+                occupied_positions.iter().map(|pos| pos.1).min()?,
+            ),
+            (
+                occupied_positions.iter().map(|pos| pos.0).max()?,
+                occupied_positions.iter().map(|pos| pos.1).max()?,
+            ),
+        ))
+    }
+
+    // renders the occupied region plus a one cell margin, labeled with board coordinates
+    pub fn render_cropped(&self, empty_glyph: char, symbols: &SymbolTable) -> String {
+        self.renderer(RenderOptions {
+            empty_glyph,
+            symbols: symbols.clone(),
+            coordinates: true,
+            cropped: true,
+            ..RenderOptions::default()
+        })
+        .to_string()
+    }
+
+    pub fn renderer(&self, options: RenderOptions) -> BoardRenderer<'_> {
+        BoardRenderer {
+            board: self,
+            options,
+        }
+    }
+
+    fn render_region(&self, cropped: bool) -> (Position, Position) {
+        let last = self.size as isize - 1;
+
+        match self.bounding_box() {
+            Some(((min_x, min_y), (max_x, max_y))) if cropped => (
+                ((min_x - 1).max(0), (min_y - 1).max(0)),
+                ((max_x + 1).min(last), (max_y + 1).min(last)),
+            ),
+            _ => ((0, 0), (last, last)),
+        }
+    }
+
+    // checks board-wide invariants that single placements can't violate on their own
+    pub fn validate_board(&self) -> Result<(), String> {
+        let occupied_cells = self.occupied_cells();
+
+        if !occupied_cells.is_empty()
+            && occupied_cells
+                .iter()
+                .all(|(_, cell)| !cell.letter.is_digit())
+        {
+            return Err("The board must contain at least one digit!".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn all_terms(&self) -> Vec<(Term, Position, Direction)> {
+        let mut terms = Vec::new();
+
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            for x in 0..self.size as isize {
+                for y in 0..self.size as isize {
+                    let is_term_start = !self.is_empty((x, y))
+                        && (self.is_out_of_bounds(move_position((x, y), -1, &direction))
+                            || self.is_empty(move_position((x, y), -1, &direction)));
+                    if !is_term_start {
+                        continue;
+                    }
+
+                    let (term, _) = self.get_term((x, y), &direction);
+                    if !term.is_singleton() {
+                        terms.push((term, (x, y), direction.clone()));
+                    }
+                }
+            }
+        }
+
+        terms
+    }
+
+    fn get_placement_terms(&self, placement: &Placement) -> Vec<(Term, Vec<Owner>)> {
+        let mut terms = Vec::new();
+        let orthogonal = placement.direction.orthogonal();
+
+        terms.push(self.get_term(placement.start_pos, &placement.direction));
+
+        for offset in 0..placement.letters.len() as isize {
+            terms.push(self.get_term(
+                move_position(placement.start_pos, offset, &placement.direction),
+                &orthogonal,
+            ));
+        }
+
+        terms
+    }
+
+    fn collect_to_term_end(
+        &self,
+        position: Position,
+        direction: &Direction,
+        iter_dir: TermDirection,
+    ) -> Vec<Position> {
+        let mut curr_iter_offset = 0;
+
+        std::iter::from_fn(move || {
+            let curr_pos = move_position(position, curr_iter_offset, direction);
+
+            if self.is_out_of_bounds(curr_pos) || self.is_empty(curr_pos) {
+                None
+            } else {
+                curr_iter_offset += iter_dir as isize;
+                Some(curr_pos)
+            }
+        })
+        .collect()
+    }
+
+    // reads in both directions, so a tile placed into a gap joins the runs on either side
+    fn get_term(&self, position: Position, direction: &Direction) -> (Term, Vec<Owner>) {
+        let start_sequence =
+            self.collect_to_term_end(position, direction, TermDirection::Decreasing);
+        let end_sequence = self.collect_to_term_end(position, direction, TermDirection::Increasing);
+        let term_sequence = start_sequence
+            .into_iter()
+            .rev()
+            .chain(end_sequence.into_iter().skip(1));
+
+        let (term, owners): (Vec<ScrabbleLetter>, Vec<Owner>) = term_sequence
+            .map(|pos| self.try_get(pos))
+            .collect::<Result<Vec<Cell>, ScrabbleRuntimeError>>()
+            .expect("BUG: term is out of bounds!")
+            .into_iter()
+            .map(|cell| (cell.letter, cell.owner))
+            .unzip();
+
+        (Term::new(&term), owners)
+    }
+}
+
+impl std::fmt::Display for GameBoard {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}", self.renderer(RenderOptions::default()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub empty_glyph: char,
+    pub coordinates: bool,
+    // highlighted cells are drawn as <x> instead of [x], compact rendering doesn't show them
+    pub highlight: Vec<Position>,
+    pub compact: bool,
+    pub cropped: bool,
+    // the display glyphs of the viewing player
+    pub symbols: SymbolTable,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            empty_glyph: DEFAULT_EMPTY_GLYPH,
+            coordinates: false,
+            highlight: Vec::new(),
+            compact: false,
+            cropped: false,
+            symbols: SymbolTable::default(),
+        }
+    }
+}
+
+// Display can't take arguments, so render settings are carried by this wrapper
+pub struct BoardRenderer<'a> {
+    board: &'a GameBoard,
+    options: RenderOptions,
+}
+
+impl std::fmt::Display for BoardRenderer<'_> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((min_x, min_y), (max_x, max_y)) = self.board.render_region(self.options.cropped);
+        let label_width = max_y.to_string().len();
+
+        if self.options.coordinates {
+            write!(formatter, "{}", " ".repeat(label_width))?;
+            for x in min_x..=max_x {
+                if self.options.compact {
+                    write!(formatter, "{}", x % 10)?;
+                } else {
+                    write!(formatter, "{:^3}", x)?;
+                }
+            }
+            writeln!(formatter)?;
+        }
+        for y in min_y..=max_y {
+            if self.options.coordinates {
+                write!(formatter, "{:>width$}", y, width = label_width)?;
+            }
+            for x in min_x..=max_x {
+                let glyph = self.board.tiles[x as usize][y as usize]
+                    .render(self.options.empty_glyph, &self.options.symbols);
+
+                if self.options.compact {
+                    write!(formatter, "{}", glyph)?;
+                } else if self.options.highlight.contains(&(x, y)) {
+                    write!(formatter, "<{}>", glyph)?;
+                } else {
+                    write!(formatter, "[{}]", glyph)?;
+                }
+            }
+            writeln!(formatter)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct Player {
+    letter_bag: Vec<ScrabbleLetter>,
+    score: isize,
+    moves_made: usize,
+    // every term formed by the player's placements, in order
+    played_terms: Vec<Term>,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct Cell {
+    pub letter: ScrabbleLetter,
+    pub owner: Owner,
+    pub locked: bool,
+}
+
+impl Cell {
+    pub fn empty() -> Cell {
+        Cell {
+            letter: ScrabbleLetter::Empty,
+            owner: Owner::None,
+            locked: false,
+        }
+    }
+
+    fn render(&self, empty_glyph: char, symbols: &SymbolTable) -> String {
+        match self.letter {
+            ScrabbleLetter::Empty => empty_glyph.to_string(),
+            letter => symbols.glyph(letter),
+        }
+    }
+}
+
+impl Player {
+    pub fn new(letter_bag: &[ScrabbleLetter]) -> Player {
+        Player {
+            letter_bag: letter_bag.to_vec(),
+            score: 0,
+            moves_made: 0,
+            played_terms: Vec::new(),
+        }
+    }
+
+    pub fn try_consume(
+        &mut self,
+        to_consume: &[ScrabbleLetter],
+    ) -> Result<(), ScrabbleRuntimeError> {
+        let are_letters_available =
+            frequency(to_consume)
+                .into_iter()
+                .all(|(letter, required_count)| {
+                    self.letter_bag.iter().filter(|val| **val == letter).count() >= required_count
+                });
+        if !are_letters_available {
+            return Err(ScrabbleRuntimeError::MissingLetters);
+        }
+
+        for letter in to_consume {
+            // availability already checked -> are_letters_available
+            let position = self
+                .letter_bag
+                .iter()
+                .position(|val| val == letter)
+                .unwrap();
+            self.letter_bag.remove(position);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn format_bag(bag: &[ScrabbleLetter], format: BagFormat) -> String {
+    match format {
+        BagFormat::Raw => bag.iter().map(ScrabbleLetter::to_string).collect(),
+        BagFormat::Spaced => bag
+            .iter()
+            .map(ScrabbleLetter::to_string)
+            .collect::<Vec<String>>()
+            .join(" "),
+        BagFormat::Counts => {
+            let mut counts = frequency(bag);
+            counts.sort();
+            counts
+                .iter()
+                .map(|(letter, count)| format!("{}:{}", letter, count))
+                .collect::<Vec<String>>()
+                .join(" ")
+        }
+    }
+}
+
+fn format_scoring_breakdown(
+    term_scores: &[(Term, i64, i64)],
+    score_changes: &[(PlayerID, isize)],
+    rules: &EvaluationRules,
+) -> String {
+    let mut breakdown = String::new();
+    for (term, value, score) in term_scores {
+        let rendered_term = term
+            .to_infix_string_with(rules)
+            .unwrap_or_else(|_| term.to_rpn_string());
+        breakdown += &format!("{} = {} -> {} points\n", rendered_term, value, score);
+    }
+    for (player_id, points) in score_changes {
+        breakdown += &format!("P{}: {:+}\n", player_id + 1, points);
+    }
+
+    breakdown
+}
+
+// all distinct letter orders of at most max_length letters that can be taken from the bag
+fn letter_sequences(bag: &[ScrabbleLetter], max_length: usize) -> Vec<Vec<ScrabbleLetter>> {
+    let mut available = frequency(bag);
+    available.sort();
+    let mut sequences = Vec::new();
+
+    extend_sequences(&mut Vec::new(), &mut available, max_length, &mut sequences);
+
+    sequences
+}
+
+fn extend_sequences(
+    prefix: &mut Vec<ScrabbleLetter>,
+    available: &mut Vec<(ScrabbleLetter, usize)>,
+    max_length: usize,
+    sequences: &mut Vec<Vec<ScrabbleLetter>>,
+) {
+    if prefix.len() == max_length {
+        return;
+    }
+
+    for index in 0..available.len() {
+        if available[index].1 == 0 {
+            continue;
+        }
+
+        available[index].1 -= 1;
+        prefix.push(available[index].0);
+        sequences.push(prefix.clone());
+        extend_sequences(prefix, available, max_length, sequences);
+        prefix.pop();
+        available[index].1 += 1;
+    }
+}
+
+fn frequency<T: Eq + Hash + Copy>(elements: &[T]) -> Vec<(T, usize)> {
+    let mut occurences = HashMap::new();
+
+    for element in elements {
+        occurences
+            .entry(*element)
+            .and_modify(|counter| *counter += 1)
+            .or_insert(1);
     }
 
     occurences.into_iter().collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_config::OwnershipRule;
+
+    // unique per test process, the tests of one run use different names
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("math_scrabble_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn init_error(config: &GameConfig, player_bags: &[Vec<ScrabbleLetter>]) -> GameInitError {
+        match ScrabbleGame::new(config, player_bags) {
+            Ok(_) => panic!("the game shouldn't have started"),
+            Err(err) => err,
+        }
+    }
+
+    // a game after the opening with the tiles of P1 already on the board
+    fn game_with_tiles(
+        config: &GameConfig,
+        rack: &str,
+        tiles: &[(Position, ScrabbleLetter)],
+    ) -> ScrabbleGame {
+        let mut game = ScrabbleGame::new(config, &[letters(rack), letters("")]).unwrap();
+        for ((x, y), letter) in tiles {
+            game.board.tiles[*x as usize][*y as usize] = Cell {
+                letter: *letter,
+                owner: Owner::Owning(0),
+                locked: false,
+            };
+        }
+        game.is_first_placement = false;
+
+        game
+    }
+
+    fn letters(letters_str: &str) -> Vec<ScrabbleLetter> {
+        letters_str
+            .chars()
+            .map(|glyph| ScrabbleLetter::from_char(glyph).expect("invalid letter in test"))
+            .collect()
+    }
+
+    #[test]
+    fn config_string_sets_up_the_game() {
+        let config: GameConfig =
+            "board_size = 7\nrack_size = 5\nscoring_mode = tile_sum\nownership_rule = split\noperators = +-\n"
+                .parse()
+                .unwrap();
+        let game = ScrabbleGame::new(&config, &[letters("12+"), letters("3")]).unwrap();
+
+        assert_eq!(game.board.size, 7);
+        assert_eq!(game.config.rack_size, Some(5));
+        assert_eq!(game.config.scoring_mode, ScoringMode::TileSum);
+        assert_eq!(game.config.ownership_rule, OwnershipRule::Split);
+        assert_eq!(
+            game.config.allowed_operators,
+            vec![ScrabbleLetter::Plus, ScrabbleLetter::Minus]
+        );
+    }
+
+    #[test]
+    fn games_can_be_set_up_with_the_config_builder() {
+        let config = GameConfig::builder()
+            .board_size(5)
+            .rack_size(3)
+            .seed(7)
+            .scoring_mode(ScoringMode::TileSum)
+            .build();
+
+        let game = ScrabbleGame::new(&config, &[letters("12+"), letters("3")]).unwrap();
+
+        assert_eq!(game.board.size, 5);
+        assert_eq!(game.config.rack_size, Some(3));
+        assert_eq!(game.config.seed, Some(7));
+        assert_eq!(game.config.scoring_mode, ScoringMode::TileSum);
+    }
+
+    #[test]
+    fn terms_for_lists_the_main_and_the_cross_term() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("12+"), letters("3*")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+
+        let terms = game
+            .terms_for(&Placement::new(
+                &letters("3*"),
+                &(3, 0),
+                &Direction::Vertical,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            terms,
+            [Term::new(&letters("3*")), Term::new(&letters("12+*"))]
+        );
+    }
+
+    #[test]
+    fn zeros_score_by_the_zero_rule() {
+        for (zero_rule, points) in [
+            (ZeroRule::Regular, 9),
+            (ZeroRule::VoidsTerm, 0),
+            (ZeroRule::Penalty, 7),
+        ] {
+            let config = GameConfig {
+                zero_rule,
+                zero_penalty: 2,
+                ..GameConfig::default()
+            };
+            let mut game = ScrabbleGame::new(&config, &[letters("90+"), letters("")]).unwrap();
+
+            game.execute_command(&"place 90+;0;0;H".parse().unwrap())
+                .unwrap();
+
+            assert_eq!(game.players[0].score, points, "{:?}", zero_rule);
+        }
+    }
+
+    #[test]
+    fn failed_consumes_leave_the_bag_untouched() {
+        let mut player = Player::new(&letters("112+"));
+
+        assert!(matches!(
+            player.try_consume(&letters("111")),
+            Err(ScrabbleRuntimeError::MissingLetters)
+        ));
+        assert_eq!(player.letter_bag, letters("112+"));
+
+        player.try_consume(&letters("1+")).unwrap();
+        assert_eq!(player.letter_bag, letters("12"));
+    }
+
+    #[test]
+    fn a_lone_tile_forms_no_terms() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("1"), letters("2")]).unwrap();
+
+        let result = game.execute_command(&"place 1;4;4;H".parse().unwrap());
+
+        assert!(matches!(result, Err(ScrabbleRuntimeError::NoTermsFormed)));
+        assert!(game.board.is_empty((4, 4)));
+        assert_eq!(game.players[0].letter_bag, letters("1"));
+    }
+
+    #[test]
+    fn undoing_the_opening_makes_the_next_move_the_opening() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;3;5;H".parse().unwrap())
+            .unwrap();
+
+        game.execute_command(&Command::Undo).unwrap();
+
+        assert!(game.is_first_placement);
+        assert!(game.board.is_empty((3, 5)));
+        assert_eq!(game.players[0].letter_bag.len(), 3);
+        assert_eq!(game.players[0].score, 0);
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn longest_term_prefers_length_then_the_smaller_position() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+5+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;2;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;0;0;H".parse().unwrap())
+            .unwrap();
+
+        let (term, value, position) = game.longest_term().unwrap();
+        assert_eq!(
+            (term, value, position),
+            (Term::new(&letters("34*")), 12, (0, 0))
+        );
+
+        game.execute_command(&"place 5+;3;2;H".parse().unwrap())
+            .unwrap();
+
+        let (term, value, position) = game.longest_term().unwrap();
+        assert_eq!(
+            (term, value, position),
+            (Term::new(&letters("12+5+")), 8, (0, 2))
+        );
+    }
+
+    #[test]
+    fn the_opening_needs_an_operator_on_the_center() {
+        let config = GameConfig {
+            opening_rule: OpeningRule::CenterOperator,
+            ..GameConfig::default()
+        };
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).unwrap();
+
+        // the 2 would cover the center
+        assert!(matches!(
+            game.execute_command(&"place 12+;4;5;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+        game.execute_command(&"place 12+;5;3;V".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.board.tiles[5][5].letter, ScrabbleLetter::Plus);
+    }
+
+    #[test]
+    fn undoing_the_opening_restores_the_center_rule() {
+        let config = GameConfig {
+            opening_rule: OpeningRule::CenterOperator,
+            ..GameConfig::default()
+        };
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;3;5;H".parse().unwrap())
+            .unwrap();
+
+        game.execute_command(&Command::Undo).unwrap();
+
+        assert!(game.is_first_placement);
+        assert!(matches!(
+            game.execute_command(&"place 12+;0;0;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+        game.execute_command(&"place 12+;3;5;H".parse().unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn placements_far_off_the_board_are_rejected() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+
+        let result = game.execute_command(&"place 12+;65535;65535;H".parse().unwrap());
+
+        assert!(result.is_err());
+        assert_eq!(game.players[0].letter_bag, letters("12+"));
+        assert!(game
+            .board
+            .tiles
+            .iter()
+            .flatten()
+            .all(|cell| cell.letter == ScrabbleLetter::Empty));
+    }
+
+    #[test]
+    fn batched_commands_run_one_after_another() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        let line = "place 12+;0;0;H && place 34*;0;2;H";
+
+        for command_str in line.split(crate::command_parsing::COMMAND_SEPARATOR) {
+            game.execute_command(&command_str.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(game.history.len(), 2);
+        assert_eq!((game.players[0].score, game.players[1].score), (3, 12));
+        assert_eq!(game.current_player, 0);
+    }
+
+    #[test]
+    fn drops_lists_every_cell_a_single_tile_scores_on() {
+        let game = game_with_tiles(
+            &GameConfig::default(),
+            "+",
+            &[
+                ((0, 0), ScrabbleLetter::Num1),
+                ((1, 0), ScrabbleLetter::Num2),
+            ],
+        );
+
+        assert_eq!(game.drops(ScrabbleLetter::Plus), [((2, 0), 3)]);
+    }
+
+    #[test]
+    fn cells_hold_the_letter_and_its_owner() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            game.board.try_get((1, 0)).unwrap(),
+            Cell {
+                letter: ScrabbleLetter::Num2,
+                owner: Owner::Owning(0),
+                locked: false,
+            }
+        );
+        let empty = game.board.try_get((0, 1)).unwrap();
+        assert_eq!(empty, Cell::empty());
+        assert_eq!(empty.owner, Owner::None);
+        assert!(game.board.try_get((10, 0)).is_err());
+    }
+
+    #[test]
+    fn the_game_is_stuck_when_no_rack_can_be_played() {
+        let stuck = ScrabbleGame::with_default_config(&[letters("+-"), letters("*")]).unwrap();
+        let playable = ScrabbleGame::with_default_config(&[letters("+-"), letters("12+")]).unwrap();
+
+        assert!(stuck.is_stuck());
+        assert!(!playable.is_stuck());
+        // without a placement the racks can still be exchanged from the pile
+        let drawing = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+        assert!(!drawing.is_stuck());
+    }
+
+    #[test]
+    fn a_seeded_deal_is_exact() {
+        let config = GameConfig::builder().seed(42).build();
+        let mut game = ScrabbleGame::new(&config, &[letters(""), letters("")]).unwrap();
+
+        game.execute_command(&Command::Deal(5)).unwrap();
+
+        assert_eq!(game.players[0].letter_bag, letters("18+*-"));
+        assert_eq!(game.pile.len(), default_tile_pool().len() - 5);
+    }
+
+    #[test]
+    fn deals_beyond_the_rack_size_are_rejected() {
+        let config = GameConfig::builder().rack_size(3).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12"), letters("")]).unwrap();
+
+        assert!(game.execute_command(&Command::Deal(4)).is_err());
+        assert_eq!(game.players[0].letter_bag, letters("12"));
+    }
+
+    #[test]
+    fn territory_shares_add_up_to_the_whole_board() {
+        let mut game = game_with_tiles(
+            &GameConfig::default(),
+            "12+",
+            &[
+                ((0, 5), ScrabbleLetter::Num7),
+                ((1, 5), ScrabbleLetter::Num7),
+            ],
+        );
+        game.board.tiles[2][5] = Cell {
+            letter: ScrabbleLetter::Plus,
+            owner: Owner::None,
+            locked: false,
+        };
+        game.current_player = 1;
+        game.players[1].letter_bag = letters("34*");
+        game.execute_command(&"place 34*;0;0;H".parse().unwrap())
+            .unwrap();
+
+        let shares = game.territory_shares();
+
+        assert_eq!(
+            shares,
+            [
+                (Owner::Owning(0), 100.0 / 3.0),
+                (Owner::Owning(1), 50.0),
+                (Owner::None, 100.0 / 6.0)
+            ]
+        );
+        let total: f64 = shares.iter().map(|(_, share)| share).sum();
+        assert!((total - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_bounding_box_covers_all_tiles() {
+        let empty = GameBoard::new(10);
+        let scattered = game_with_tiles(
+            &GameConfig::default(),
+            "",
+            &[
+                ((2, 7), ScrabbleLetter::Num1),
+                ((6, 3), ScrabbleLetter::Num2),
+                ((4, 5), ScrabbleLetter::Plus),
+            ],
+        );
+
+        assert_eq!(empty.bounding_box(), None);
+        assert_eq!(scattered.board.bounding_box(), Some(((2, 3), (6, 7))));
+    }
+
+    #[test]
+    fn the_cropped_render_keeps_a_margin_around_the_tiles() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;3;4;H".parse().unwrap())
+            .unwrap();
+
+        let rendered = game
+            .board
+            .render_cropped(DEFAULT_EMPTY_GLYPH, &SymbolTable::default());
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        // a header line and the rows 3 to 5, each with the columns 2 to 6
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "  2  3  4  5  6 ");
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line.chars().count() == 1 + 5 * 3));
+        assert_eq!(lines[2], "4[.][1][2][+][.]");
+    }
+
+    #[test]
+    fn empty_cells_use_the_configured_glyph() {
+        let config: GameConfig = "board_size = 3\nempty_glyph = _\n".parse().unwrap();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+
+        let rendered = game
+            .board
+            .renderer(RenderOptions {
+                empty_glyph: game.config.empty_glyph,
+                ..RenderOptions::default()
+            })
+            .to_string();
+
+        assert_eq!(rendered, "[_][_][_]\n[1][2][+]\n[_][_][_]\n");
+    }
+
+    #[test]
+    fn render_options_change_the_board_drawing() {
+        let config = GameConfig::builder().board_size(3).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+        let render = |options: RenderOptions| game.board.renderer(options).to_string();
+
+        assert_eq!(
+            render(RenderOptions::default()),
+            "[.][.][.]\n[1][2][+]\n[.][.][.]\n"
+        );
+        assert_eq!(
+            render(RenderOptions {
+                compact: true,
+                coordinates: true,
+                ..RenderOptions::default()
+            }),
+            " 012\n0...\n112+\n2...\n"
+        );
+        assert_eq!(
+            render(RenderOptions {
+                highlight: vec![(1, 1)],
+                cropped: true,
+                ..RenderOptions::default()
+            }),
+            "[.][.][.]\n[1]<2>[+]\n[.][.][.]\n"
+        );
+    }
+
+    #[test]
+    fn boards_without_digits_are_invalid() {
+        let operators_only = game_with_tiles(
+            &GameConfig::default(),
+            "",
+            &[
+                ((0, 0), ScrabbleLetter::Plus),
+                ((1, 0), ScrabbleLetter::Minus),
+            ],
+        );
+        let with_digit = game_with_tiles(
+            &GameConfig::default(),
+            "",
+            &[
+                ((0, 0), ScrabbleLetter::Plus),
+                ((1, 0), ScrabbleLetter::Num1),
+            ],
+        );
+
+        assert!(GameBoard::new(10).validate_board().is_ok());
+        assert!(operators_only.board.validate_board().is_err());
+        assert!(with_digit.board.validate_board().is_ok());
+    }
+
+    #[test]
+    fn move_numbers_count_per_player() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+56-"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;0;2;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 56-;0;4;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.move_numbers(), [(0, 1), (1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn the_min_rack_forms_the_target() {
+        let game = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+
+        let rack = game.min_rack_for(6);
+
+        assert_eq!(rack.len(), 3);
+        assert_eq!(Term::new(&rack).evaluate(), Ok(6));
+    }
+
+    #[test]
+    fn the_best_response_is_the_highest_scoring_move() {
+        // 8 1 - is worth 7, 1 8 - only -7, and the first free cell wins ties
+        let game = ScrabbleGame::with_default_config(&[letters("8-1"), letters("")]).unwrap();
+
+        let (player_id, placement, points) = game.best_response().unwrap();
+
+        assert_eq!(
+            (player_id, placement.to_string(), points),
+            (0, "81-;0;0;H".to_string(), 7)
+        );
+    }
+
+    #[test]
+    fn the_breakdown_lists_terms_and_score_changes() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("12+"), letters("3*")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+        let placement = Placement::new(&letters("3*"), &(3, 1), &Direction::Horizontal);
+        let outcome = game.preview_placement(1, &placement).unwrap();
+
+        let breakdown = format_scoring_breakdown(
+            &outcome.term_scores,
+            &outcome.score_changes,
+            &game.evaluation_rules(),
+        );
+
+        assert_eq!(breakdown, "(1+2)*3 = 9 -> 9 points\nP1: +9\n");
+    }
+
+    #[test]
+    fn a_single_player_is_not_enough() {
+        let err = init_error(&GameConfig::default(), &[letters("12+")]);
+
+        assert!(matches!(err, GameInitError::NotEnoughPlayers(MIN_PLAYERS)));
+    }
+
+    #[test]
+    fn bags_larger_than_the_rack_are_rejected() {
+        let config = GameConfig {
+            rack_size: Some(3),
+            ..GameConfig::default()
+        };
+
+        let err = init_error(&config, &[letters("12+"), letters("1234")]);
+
+        assert!(matches!(
+            err,
+            GameInitError::BagTooLarge {
+                player: 1,
+                rack_size: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn an_empty_board_is_rejected() {
+        let config = GameConfig {
+            board_size: 0,
+            ..GameConfig::default()
+        };
+
+        assert!(matches!(
+            init_error(&config, &[letters("12+"), letters("34*")]),
+            GameInitError::InvalidConfig(_)
+        ));
+    }
+
+    #[test]
+    fn the_diff_lists_the_placed_tiles() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        let before = game.board.clone();
+
+        game.execute_command(&"place 12+;4;2;V".parse().unwrap())
+            .unwrap();
+
+        let placed = |letter| Cell {
+            letter,
+            owner: Owner::Owning(0),
+            locked: false,
+        };
+        assert_eq!(
+            before.diff(&game.board),
+            [
+                ((4, 2), Cell::empty(), placed(ScrabbleLetter::Num1)),
+                ((4, 3), Cell::empty(), placed(ScrabbleLetter::Num2)),
+                ((4, 4), Cell::empty(), placed(ScrabbleLetter::Plus)),
+            ]
+        );
+        assert!(game.board.diff(&game.board).is_empty());
+    }
+
+    #[test]
+    fn cross_terms_are_scored_by_the_cross_term_rule() {
+        for (cross_terms, valid_cross_points, invalid_cross_points) in [
+            (CrossTermRule::Score, Some(22), None),
+            (CrossTermRule::Validate, Some(13), None),
+            (CrossTermRule::Ignore, Some(13), Some(13)),
+        ] {
+            let config = GameConfig {
+                cross_terms,
+                ..GameConfig::default()
+            };
+            // the placement forms 6 7 + downwards and ends the row 4 5 + or 4 +
+            let points_with_row = |row: &[(Position, ScrabbleLetter)]| {
+                let mut game = game_with_tiles(&config, "67+", row);
+                game.execute_command(&"place 67+;2;0;V".parse().unwrap())
+                    .ok()
+                    .map(|_| game.scores()[0])
+            };
+
+            assert_eq!(
+                points_with_row(&[
+                    ((0, 2), ScrabbleLetter::Num4),
+                    ((1, 2), ScrabbleLetter::Num5)
+                ]),
+                valid_cross_points,
+                "{}",
+                cross_terms
+            );
+            assert_eq!(
+                points_with_row(&[((1, 2), ScrabbleLetter::Num4)]),
+                invalid_cross_points,
+                "{}",
+                cross_terms
+            );
+        }
+    }
+
+    #[test]
+    fn the_joker_picks_an_operator_that_makes_the_term_valid() {
+        let config: GameConfig = "operators = +-*/%^#".parse().unwrap();
+        let mut game = ScrabbleGame::new(&config, &[letters("10/#"), letters("")]).unwrap();
+
+        // dividing by zero is invalid, the joker falls back to the best other operator
+        assert!(game
+            .execute_command(&"place 10/;0;0;H".parse().unwrap())
+            .is_err());
+        game.execute_command(&"place 10#;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.scores(), [1, 0]);
+    }
+
+    #[test]
+    fn locked_tiles_survive_clearing_and_undo() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&Command::Lock((1, 0))).unwrap();
+
+        game.board.clear((1, 0));
+        assert_eq!(game.board.tiles[1][0].letter, ScrabbleLetter::Num2);
+
+        assert!(matches!(
+            game.execute_command(&Command::Undo),
+            Err(ScrabbleRuntimeError::LockedCell((1, 0)))
+        ));
+        assert_eq!(game.history.len(), 1);
+        assert_eq!(game.board.occupied_cells().len(), 3);
+    }
+
+    #[test]
+    fn achievable_values_of_a_small_rack() {
+        let game = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+
+        assert_eq!(game.achievable_values(&letters("23+")), [5]);
+        // a leading minus negates the number after it, a trailing one isn't valid
+        assert_eq!(game.achievable_values(&letters("23-")), [-3, -2, -1, 1]);
+    }
+
+    #[test]
+    fn seeded_games_deal_the_same_racks() {
+        let config = GameConfig::builder().seed(42).rack_size(4).build();
+
+        let game = ScrabbleGame::with_dealt_racks(&config, 2).unwrap();
+        let again = ScrabbleGame::with_dealt_racks(&config, 2).unwrap();
+
+        assert_eq!(game.players[0].letter_bag, letters("18+*"));
+        assert_eq!(game.players[1].letter_bag, letters("-+-+"));
+        assert_eq!(game.pile, again.pile);
+        assert_eq!(game.pile.len(), default_tile_pool().len() - 8);
+    }
+
+    #[test]
+    fn the_gap_is_the_distance_to_the_leader() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.gap(0).unwrap(), 0);
+        assert_eq!(game.gap(1).unwrap(), 3);
+    }
+
+    #[test]
+    fn reversible_terms_may_be_read_backwards() {
+        let config = GameConfig {
+            reversible_terms: true,
+            ..GameConfig::default()
+        };
+        let mut reversible = ScrabbleGame::new(&config, &[letters("+21"), letters("")]).unwrap();
+        let mut regular =
+            ScrabbleGame::with_default_config(&[letters("+21"), letters("")]).unwrap();
+
+        // only the reading 1 2 + is a valid term
+        reversible
+            .execute_command(&"place +21;0;0;H".parse().unwrap())
+            .unwrap();
+        assert!(regular
+            .execute_command(&"place +21;0;0;H".parse().unwrap())
+            .is_err());
+
+        assert_eq!(reversible.scores(), [3, 0]);
+    }
+
+    #[test]
+    fn bags_are_formatted_as_configured() {
+        let bag = letters("1+1");
+
+        assert_eq!(format_bag(&bag, BagFormat::Raw), "1+1");
+        assert_eq!(format_bag(&bag, BagFormat::Spaced), "1 + 1");
+        assert_eq!(format_bag(&bag, BagFormat::Counts), "1:2 +:1");
+    }
+
+    #[test]
+    fn the_center_of_odd_and_even_boards() {
+        assert_eq!(GameBoard::new(9).center(), (4, 4));
+        assert_eq!(GameBoard::new(10).center(), (5, 5));
+        assert_eq!(GameBoard::new(1).center(), (0, 0));
+    }
+
+    #[test]
+    fn players_with_unplayable_racks_cannot_move() {
+        let game = ScrabbleGame::with_default_config(&[letters("+-"), letters("12+")]).unwrap();
+
+        assert_eq!(game.players_able_to_move(), [1]);
+    }
+
+    #[test]
+    fn unique_term_values_reject_a_repeated_value() {
+        let config = GameConfig {
+            unique_term_values: true,
+            ..GameConfig::default()
+        };
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("21+34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert!(matches!(
+            game.execute_command(&"place 21+;0;2;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+        game.execute_command(&"place 34*;0;2;H".parse().unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn csv_export_of_a_small_board() {
+        let config = GameConfig::builder().board_size(3).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("")]).unwrap();
+        game.execute_command(&"place 12+;2;0;V".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.board.to_csv(), "0,1,2\n,,1\n,,2\n,,+\n");
+    }
+
+    #[test]
+    fn exported_csv_imports_to_the_same_board() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;2;3;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;7;0;V".parse().unwrap())
+            .unwrap();
+        let path = temp_path("board.csv");
+
+        game.execute_command(&Command::ExportCsv(path.clone()))
+            .unwrap();
+        let mut imported = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+        imported
+            .execute_command(&Command::ImportCsv(path.clone()))
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let letters_of = |board: &GameBoard| {
+            board
+                .occupied_cells()
+                .into_iter()
+                .map(|(position, cell)| (position, cell.letter))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(letters_of(&imported.board), letters_of(&game.board));
+        assert!(imported
+            .board
+            .occupied_cells()
+            .iter()
+            .all(|(_, cell)| cell.owner == Owner::None));
+        assert!(!imported.is_first_placement);
+    }
+
+    #[test]
+    fn operands_above_the_maximum_are_rejected() {
+        let config = GameConfig::builder().max_operand(5).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("72+3"), letters("")]).unwrap();
+
+        assert!(matches!(
+            game.execute_command(&"place 72+;0;0;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+        game.execute_command(&"place 32+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.scores(), [5, 0]);
+    }
+
+    #[test]
+    fn score_deltas_predict_the_score_change() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("3*4+")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+        let placement = Placement::new(&letters("3*"), &(3, 1), &Direction::Horizontal);
+        let before = game.scores();
+
+        let deltas = game.score_deltas(&placement).unwrap();
+        game.execute_command(&Command::Place(placement)).unwrap();
+
+        // P1 holds most of the extended term, so the points go to P1
+        assert_eq!(deltas, [(0, 9)]);
+        let after = game.scores();
+        for (player_id, delta) in deltas {
+            assert_eq!(after[player_id] - before[player_id], delta);
+        }
+        assert_eq!(after[1], before[1]);
+    }
+
+    #[test]
+    fn the_potential_is_the_score_of_the_best_legal_move() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*-")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+
+        let best_points = game
+            .legal_moves(1)
+            .iter()
+            .map(|placement| game.preview_placement(1, placement).unwrap().points_for(1))
+            .max()
+            .unwrap();
+
+        assert_eq!(game.potential(), best_points);
+        assert_eq!(game.potential(), 12);
+    }
+
+    #[test]
+    fn relative_moves_are_placed_from_the_first_move() {
+        let config = GameConfig::builder().relative_coordinates(true).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;4;3;H".parse().unwrap())
+            .unwrap();
+
+        game.execute_command(&"place 34*;-2;2;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.board.tiles[2][5].letter, ScrabbleLetter::Num3);
+        assert_eq!(game.history[1].placement.start_pos, (2, 5));
+        assert!(matches!(
+            game.execute_command(&"place 5;-5;0;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::PositionOutOfBounds((-1, 3)))
+        ));
+    }
+
+    #[test]
+    fn pool_limits_allow_the_whole_supply_of_a_tile() {
+        let config = GameConfig {
+            enforce_pool_limits: true,
+            ..GameConfig::default()
+        };
+
+        let game = ScrabbleGame::new(&config, &[letters("55"), letters("55")]).unwrap();
+        let one_too_many = init_error(&config, &[letters("55"), letters("555")]);
+
+        assert!(!game.pile.contains(&ScrabbleLetter::Num5));
+        assert_eq!(game.pile.len(), default_tile_pool().len() - 4);
+        assert!(matches!(
+            one_too_many,
+            GameInitError::PoolExceeded {
+                letter: ScrabbleLetter::Num5,
+                count: 5,
+                limit: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn the_highest_scoring_term_and_its_player() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+56-"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;0;2;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 56-;0;4;H".parse().unwrap())
+            .unwrap();
+
+        let (player_id, term, score) = game.highest_scoring_term().unwrap();
+
+        assert_eq!(
+            (player_id, term.to_rpn_string(), score),
+            (1, "3 4 *".to_string(), 12)
+        );
+    }
+
+    #[test]
+    fn placements_over_the_edge_fail_or_are_clipped() {
+        let tiles = [
+            ((7, 0), ScrabbleLetter::Num1),
+            ((8, 0), ScrabbleLetter::Num2),
+        ];
+        let strict_config = GameConfig::default();
+        let clip_config = GameConfig::builder().edge_rule(EdgeRule::Clip).build();
+        let mut strict = game_with_tiles(&strict_config, "+34", &tiles);
+        let mut clipped = game_with_tiles(&clip_config, "+34", &tiles);
+
+        assert!(strict
+            .execute_command(&"place +34;9;0;H".parse().unwrap())
+            .is_err());
+        clipped
+            .execute_command(&"place +34;9;0;H".parse().unwrap())
+            .unwrap();
+
+        // only the + fits on the board, the other tiles stay in the rack
+        assert_eq!(clipped.board.tiles[9][0].letter, ScrabbleLetter::Plus);
+        assert_eq!(clipped.players[0].letter_bag, letters("34"));
+        assert_eq!(clipped.scores(), [3, 0]);
+        assert_eq!(strict.players[0].letter_bag, letters("+34"));
+    }
+
+    #[test]
+    fn empty_runs_around_a_tile() {
+        let config = GameConfig::builder().board_size(3).build();
+        let game = game_with_tiles(&config, "", &[((1, 0), ScrabbleLetter::Num1)]);
+
+        let runs: Vec<(Position, bool, usize)> = game
+            .board
+            .empty_runs()
+            .into_iter()
+            .map(|(start, direction, length)| {
+                (start, matches!(direction, Direction::Horizontal), length)
+            })
+            .collect();
+
+        assert_eq!(
+            runs,
+            [
+                ((0, 0), true, 1),
+                ((2, 0), true, 1),
+                ((0, 1), true, 3),
+                ((0, 2), true, 3),
+                ((0, 0), false, 3),
+                ((1, 1), false, 2),
+                ((2, 0), false, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn reseeding_makes_deals_repeatable_until_the_first_move() {
+        let deal_after_seed = || {
+            let mut game = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+            game.execute_command(&"seed 42".parse().unwrap()).unwrap();
+            game.execute_command(&"deal 5".parse().unwrap()).unwrap();
+            game
+        };
+        let mut game = deal_after_seed();
+
+        assert_eq!(
+            game.players[0].letter_bag,
+            deal_after_seed().players[0].letter_bag
+        );
+        assert_eq!(game.pile, deal_after_seed().pile);
+
+        game.players[0].letter_bag = letters("12+");
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        assert!(matches!(
+            game.execute_command(&"seed 7".parse().unwrap()),
+            Err(ScrabbleRuntimeError::GameAlreadyStarted)
+        ));
+    }
+
+    #[test]
+    fn placed_terms_are_found_by_value() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        assert!(!game.has_term_value(3));
+
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert!(game.has_term_value(3));
+        assert!(!game.has_term_value(12));
+    }
+
+    #[test]
+    fn players_may_not_repeat_their_own_terms() {
+        let config = GameConfig::builder().no_repeated_terms(true).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+12+"), letters("12+")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        // another player may form the same term
+        game.execute_command(&"place 12+;0;2;H".parse().unwrap())
+            .unwrap();
+        assert!(matches!(
+            game.execute_command(&"place 12+;0;4;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+    }
+
+    #[test]
+    fn traced_errors_show_the_evaluation_up_to_the_failure() {
+        let config = GameConfig::builder().trace_errors(true).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("1+2"), letters("")]).unwrap();
+
+        let result = game.execute_command(&"place 1+2;0;0;H".parse().unwrap());
+
+        let Err(ScrabbleRuntimeError::InvalidPlacement(message)) = result else {
+            panic!("the placement should be invalid");
+        };
+        assert_eq!(
+            message,
+            "The placement leads to invalid terms!\n1 + 2:\n  1 -> [1]\n  + -> The Operator + expects 2 arguments, but received only 1!"
+        );
+    }
+
+    #[test]
+    fn explaining_the_multiplication_tile() {
+        let game = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+        let without_multiplication = ScrabbleGame::new(
+            &"operators = +-".parse().unwrap(),
+            &[letters(""), letters("")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            game.explain(ScrabbleLetter::Dot),
+            "'*' is an operator, it multiplies the two topmost numbers."
+        );
+        assert!(without_multiplication
+            .explain(ScrabbleLetter::Dot)
+            .ends_with(" It is not allowed in this game."));
+    }
+
+    #[test]
+    fn the_territory_winner_can_trail_on_points() {
+        for (victory_rule, winner) in [(VictoryRule::Score, 1), (VictoryRule::Territory, 0)] {
+            let config = GameConfig::builder().victory_rule(victory_rule).build();
+            let mut game =
+                ScrabbleGame::new(&config, &[letters("12+12+"), letters("99*")]).unwrap();
+            game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+                .unwrap();
+            game.execute_command(&"place 99*;0;2;H".parse().unwrap())
+                .unwrap();
+            game.execute_command(&"place 12+;0;4;H".parse().unwrap())
+                .unwrap();
+
+            assert_eq!(game.scores(), [6, 81]);
+            assert_eq!(game.owned_cells(), [6, 3]);
+            assert_eq!(game.territory_winner(), Some(0));
+            assert_eq!(game.winner(), Some(winner), "{}", victory_rule);
+        }
+    }
+
+    #[test]
+    fn identical_states_hash_equal() {
+        let bags = [letters("12+"), letters("34*")];
+        let mut game = ScrabbleGame::with_default_config(&bags).unwrap();
+        // rack order doesn't matter for the state
+        let reordered =
+            ScrabbleGame::with_default_config(&[letters("+21"), letters("*43")]).unwrap();
+        let opening_hash = game.state_hash();
+
+        assert_eq!(opening_hash, reordered.state_hash());
+
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        assert_ne!(game.state_hash(), opening_hash);
+
+        game.execute_command(&Command::Undo).unwrap();
+        assert_eq!(game.state_hash(), opening_hash);
+    }
+
+    #[test]
+    fn passing_back_to_the_opening_position_is_a_draw() {
+        let config = GameConfig::builder().repetition_limit(2).build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("34+")]).unwrap();
+
+        game.execute_command(&Command::Pass).unwrap();
+        assert!(!game.is_drawn_by_repetition());
+        game.execute_command(&Command::Pass).unwrap();
+        assert!(game.is_drawn_by_repetition());
+    }
+
+    #[test]
+    fn dealt_racks_are_the_opening_position() {
+        let config = GameConfig::builder().seed(7).repetition_limit(2).build();
+        let mut game = ScrabbleGame::with_dealt_racks(&config, 2).unwrap();
+
+        assert_eq!(game.seen_states.get(&game.state_hash()), Some(&1));
+        game.execute_command(&Command::Pass).unwrap();
+        game.execute_command(&Command::Pass).unwrap();
+        assert!(game.is_drawn_by_repetition());
+    }
+
+    #[test]
+    fn undo_forgets_the_state_reached_by_the_move() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34+")]).unwrap();
+
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        let reached_state = game.state_hash();
+        game.execute_command(&Command::Pass).unwrap();
+        let passed_state = game.state_hash();
+        game.execute_command(&Command::Undo).unwrap();
+
+        assert_eq!(game.seen_states.get(&reached_state), Some(&0));
+        assert_eq!(game.seen_states.get(&passed_state), Some(&1));
+    }
+
+    #[test]
+    fn a_tile_in_the_gap_completes_an_operation() {
+        let mut game = game_with_tiles(
+            &GameConfig::default(),
+            "2",
+            &[
+                ((0, 0), ScrabbleLetter::Num1),
+                ((2, 0), ScrabbleLetter::Plus),
+            ],
+        );
+
+        game.execute_command(&"place 2;1;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.history[0].points, 3);
+    }
+
+    #[test]
+    fn placing_tiles_keeps_the_census() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        let census = game.tile_census();
+
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.tile_census(), census);
+        assert_eq!(census.values().sum::<usize>(), game.pile.len() + 6);
+    }
+
+    #[test]
+    fn the_move_notation_lists_player_placement_and_points() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;0;2;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.move_notation(), "P1 12+;0;0;H +3\nP2 34*;0;2;H +12\n");
+    }
+
+    #[test]
+    fn folded_operands_above_the_maximum_are_rejected() {
+        let config = GameConfig::builder()
+            .fold_digits(true)
+            .max_operand(99)
+            .build();
+        let mut game = ScrabbleGame::new(&config, &[letters("10099"), letters("")]).unwrap();
+
+        assert!(matches!(
+            game.execute_command(&"place 100;0;0;H".parse().unwrap()),
+            Err(ScrabbleRuntimeError::InvalidPlacement(_))
+        ));
+        game.execute_command(&"place 99;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.scores(), [99, 0]);
+    }
+
+    #[test]
+    fn a_tile_in_the_gap_joins_two_runs() {
+        let config = GameConfig {
+            fold_digits: true,
+            ..GameConfig::default()
+        };
+        let mut game = game_with_tiles(
+            &config,
+            "2",
+            &[
+                ((0, 0), ScrabbleLetter::Num1),
+                ((2, 0), ScrabbleLetter::Num3),
+            ],
+        );
+
+        game.execute_command(&"place 2;1;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.board.all_terms().len(), 1);
+        assert_eq!(game.history[0].points, 123);
+    }
+
+    #[test]
+    fn the_configured_starting_player_moves_first() {
+        let config = GameConfig::builder()
+            .starting_player(StartingPlayer::Fixed(1))
+            .build();
+        let mut game =
+            ScrabbleGame::new(&config, &[letters("12+"), letters("34*"), letters("")]).unwrap();
+
+        assert_eq!(game.current_player(), 1);
+        game.execute_command(&"place 34*;0;0;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.history[0].player, 1);
+        assert_eq!(game.current_player(), 2);
+    }
+
+    #[test]
+    fn moves_outside_the_region_are_ignored() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("99*1+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 99*;0;0;H".parse().unwrap())
+            .unwrap();
+
+        let (best, points) = game.best_move_in_region(0, (0, 0), (9, 9)).unwrap();
+        assert_eq!((best.to_string(), points), ("1+;3;0;H".to_string(), 82));
+
+        // extending the top row isn't possible below it
+        let (best, points) = game.best_move_in_region(0, (0, 1), (9, 9)).unwrap();
+        assert_eq!((best.to_string(), points), ("1+;0;1;V".to_string(), 10));
+    }
+
+    #[test]
+    fn notes_are_stored_per_cell() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+
+        game.execute_command(&"note 2 1 first".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"note 0 3 second".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"note 2 1 replaced".parse().unwrap())
+            .unwrap();
+        assert!(game
+            .execute_command(&"note 99 0 off the board".parse().unwrap())
+            .is_err());
+
+        assert_eq!(
+            game.notes.iter().collect::<Vec<_>>(),
+            [
+                (&(0, 3), &"second".to_string()),
+                (&(2, 1), &"replaced".to_string())
+            ]
+        );
+        game.execute_command(&Command::Notes).unwrap();
+        assert_eq!(game.current_player, 0);
+    }
+
+    #[test]
+    fn the_expected_draw_value_averages_the_digits_in_the_pile() {
+        let mut game = ScrabbleGame::with_default_config(&[letters(""), letters("")]).unwrap();
+        // the 39 digits of the default pool add up to 162
+        assert_eq!(game.expected_draw_value(), 162.0 / 39.0);
+
+        game.pile = letters("19++");
+        assert_eq!(game.expected_draw_value(), 5.0);
+        game.pile = letters("+-");
+        assert_eq!(game.expected_draw_value(), 0.0);
+    }
+
+    #[test]
+    fn rotating_turns_the_top_row_into_the_right_column() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        let rotated = game.board.rotated();
+
+        assert_eq!(rotated.tiles[9][0], game.board.tiles[0][0]);
+        assert_eq!(rotated.tiles[9][1].letter, ScrabbleLetter::Num2);
+        assert_eq!(rotated.tiles[9][2].letter, ScrabbleLetter::Plus);
+        assert_eq!(rotated.tiles[9][2].owner, Owner::Owning(0));
+        assert_eq!(rotated.occupied_cells().len(), 3);
+    }
+
+    #[test]
+    fn winning_moves_end_the_game_in_the_lead() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("31-"), letters("")]).unwrap();
+        game.players[1].score = 1;
+
+        let winning_moves = game.winning_moves(0);
+
+        // 3 1 - ends the game 2 to 1, 1 3 - would lose and shorter moves leave tiles to play
+        assert!(!winning_moves.is_empty());
+        assert!(winning_moves
+            .iter()
+            .all(|placement| placement.letters == letters("31-")));
+        assert!(game
+            .legal_moves(0)
+            .iter()
+            .any(|placement| placement.letters == letters("13-")));
+        assert!(game.winning_moves(1).is_empty());
+    }
+
+    #[test]
+    fn multiplication_is_typed_as_star_and_shown_as_times() {
+        let symbols: SymbolTable = ";*×".parse().unwrap();
+        let config = GameConfig::builder()
+            .board_size(3)
+            .symbol_table(0, symbols.clone())
+            .build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12*"), letters("")]).unwrap();
+
+        let command = Command::parse_with("place 12*;0;0;H", &game.symbol_table(0)).unwrap();
+        game.execute_command(&command).unwrap();
+
+        assert_eq!(game.board.tiles[2][0].letter, ScrabbleLetter::Dot);
+        let rendered = game
+            .board
+            .renderer(RenderOptions {
+                symbols,
+                compact: true,
+                ..RenderOptions::default()
+            })
+            .to_string();
+        assert_eq!(rendered, "12×\n...\n...\n");
+    }
+
+    #[test]
+    fn the_next_player_wraps_around_to_p1() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*"), letters("56-")])
+                .unwrap();
+
+        assert_eq!(game.current_and_next_player(), (0, 1));
+        game.execute_command(&Command::Pass).unwrap();
+        game.execute_command(&Command::Pass).unwrap();
+
+        assert_eq!(game.current_and_next_player(), (2, 0));
+    }
+
+    #[test]
+    fn csv_with_the_wrong_size_is_rejected_before_allocating() {
+        let csv = ",".repeat(100_000);
+
+        assert!(matches!(
+            GameBoard::from_csv(&csv, 10),
+            Err(ScrabbleRuntimeError::InvalidCsv(_))
+        ));
+    }
+
+    #[test]
+    fn csv_import_starts_the_move_counts_over() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("12+"), letters("")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+
+        let csv = GameBoard::new(10).to_csv();
+        let path = temp_path("empty_board.csv");
+        std::fs::write(&path, csv).unwrap();
+        game.execute_command(&Command::ImportCsv(path.clone()))
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(game.history.is_empty());
+        assert_eq!(game.scores(), [0, 0]);
+        assert_eq!(game.players[0].moves_made, 0);
+        assert!(game.players[0].played_terms.is_empty());
+        assert_eq!(game.seen_states.len(), 1);
+    }
+
+    #[test]
+    fn exported_moves_replay_to_the_same_state() {
+        let bags = [letters("12+34*"), letters("56+")];
+        let mut game = ScrabbleGame::with_default_config(&bags).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&Command::Pass).unwrap();
+        game.execute_command(&"place 34*;0;4;H".parse().unwrap())
+            .unwrap();
+        let path = temp_path("moves.txt");
+
+        game.execute_command(&Command::ExportMoves(path.clone()))
+            .unwrap();
+        let mut replayed = ScrabbleGame::with_default_config(&bags).unwrap();
+        replayed
+            .execute_command(&Command::ImportMoves(path.clone()))
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(game.move_notation(), "P1 12+;0;0;H +3\nP1 34*;0;4;H +12\n");
+        assert_eq!(replayed.move_notation(), game.move_notation());
+        assert_eq!(replayed.state_hash(), game.state_hash());
+    }
+
+    #[test]
+    fn replayed_moves_have_to_score_as_noted() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("12+"), letters("")]).unwrap();
+
+        assert!(matches!(
+            game.replay_moves("P1 12+;0;0;H +4\n"),
+            Err(ScrabbleRuntimeError::InvalidMoveNotation { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn a_failing_replay_leaves_the_game_untouched() {
+        let bags = [letters("12+"), letters("34*")];
+        let mut game = ScrabbleGame::with_default_config(&bags).unwrap();
+        let state = game.state_hash();
+
+        assert!(matches!(
+            game.replay_moves("P1 12+;0;0;H +3\nP2 34*;0;2;H +13\n"),
+            Err(ScrabbleRuntimeError::InvalidMoveNotation { line: 2, .. })
+        ));
+        assert!(game.history.is_empty());
+        assert!(game.board.occupied_cells().is_empty());
+        assert_eq!(game.state_hash(), state);
+        assert_eq!(game.current_player, 0);
+    }
+
+    #[test]
+    fn dealing_needs_enough_tiles_in_the_pool() {
+        let config = GameConfig {
+            rack_size: Some(30),
+            ..GameConfig::default()
+        };
+
+        let err = match ScrabbleGame::with_dealt_racks(&config, 2) {
+            Ok(_) => panic!("the pool only holds 58 tiles"),
+            Err(err) => err,
+        };
+
+        assert!(matches!(
+            err,
+            GameInitError::NotEnoughTiles {
+                needed: 60,
+                available: 58
+            }
+        ));
+    }
+
+    #[test]
+    fn bags_beyond_the_pool_limits_are_rejected() {
+        let config = GameConfig {
+            enforce_pool_limits: true,
+            ..GameConfig::default()
+        };
+
+        let err = init_error(&config, &[letters("555"), letters("55")]);
+
+        assert!(matches!(
+            err,
+            GameInitError::PoolExceeded {
+                letter: ScrabbleLetter::Num5,
+                count: 5,
+                limit: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let bags = [letters("12+"), letters("34*")];
+        let mut symbol_tables = BTreeMap::new();
+        symbol_tables.insert(2, SymbolTable::new(&[]));
+        let invalid_configs = [
+            GameConfig {
+                board_size: 0,
+                ..GameConfig::default()
+            },
+            GameConfig {
+                board_size: 1_000_000,
+                ..GameConfig::default()
+            },
+            GameConfig {
+                allowed_operators: vec![ScrabbleLetter::Num1],
+                ..GameConfig::default()
+            },
+            GameConfig {
+                starting_player: StartingPlayer::Fixed(2),
+                ..GameConfig::default()
+            },
+            GameConfig {
+                symbol_tables,
+                ..GameConfig::default()
+            },
+        ];
+
+        for config in &invalid_configs {
+            assert!(matches!(
+                init_error(config, &bags),
+                GameInitError::InvalidConfig(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn the_largest_board_is_still_allowed() {
+        let config = GameConfig {
+            board_size: MAX_BOARD_SIZE,
+            ..GameConfig::default()
+        };
+
+        assert!(ScrabbleGame::new(&config, &[letters("12+"), letters("34*")]).is_ok());
+    }
+
+    #[test]
+    fn unknown_players_have_no_moves_and_no_gap() {
+        let game = ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+
+        assert!(game.legal_moves(2).is_empty());
+        assert!(game.winning_moves(2).is_empty());
+        assert!(game.best_move_in_region(2, (0, 0), (9, 9)).is_none());
+        assert!(matches!(
+            game.gap(2),
+            Err(ScrabbleRuntimeError::PlayerIDOutOfBOunds(2))
+        ));
+        assert_eq!(game.gap(1).unwrap(), 0);
+    }
+
+    #[test]
+    fn rotating_twice_flips_the_board() {
+        let mut game =
+            ScrabbleGame::with_default_config(&[letters("12+"), letters("34*")]).unwrap();
+        game.execute_command(&"place 12+;0;0;H".parse().unwrap())
+            .unwrap();
+        game.execute_command(&"place 34*;0;4;H".parse().unwrap())
+            .unwrap();
+
+        let flipped = game.board.rotated().rotated();
+        let size = game.board.size;
+
+        for x in 0..size {
+            for y in 0..size {
+                assert_eq!(
+                    flipped.tiles[size - 1 - x][size - 1 - y],
+                    game.board.tiles[x][y]
+                );
+            }
+        }
+        assert_eq!(flipped.tiles[9][9].letter, ScrabbleLetter::Num1);
+        assert_eq!(flipped.tiles[7][5].owner, Owner::Owning(1));
+    }
+
+    #[test]
+    fn supplied_racks_leave_no_pile_without_pool_limits() {
+        let bags = [letters("12+"), letters("34*")];
+        let game = ScrabbleGame::with_default_config(&bags).unwrap();
+        let limited = GameConfig::builder().enforce_pool_limits(true).build();
+        let limited_game = ScrabbleGame::new(&limited, &bags).unwrap();
+
+        assert!(game.pile.is_empty());
+        assert_eq!(game.tile_census().values().sum::<usize>(), 6);
+        assert_eq!(limited_game.pile.len(), default_tile_pool().len() - 6);
+    }
+
+    #[test]
+    fn drawing_tiles_shrinks_the_pile() {
+        let config = GameConfig::builder().seed(42).build();
+        let mut game = ScrabbleGame::new(&config, &[letters(""), letters("")]).unwrap();
+        let pool_size = default_tile_pool().len();
+
+        assert_eq!(game.pile_remaining(), pool_size);
+        game.execute_command(&Command::Deal(5)).unwrap();
+        assert_eq!(game.pile_remaining(), pool_size - 5);
+        game.execute_command(&Command::Pass).unwrap();
+        game.execute_command(&Command::Deal(3)).unwrap();
+        assert_eq!(game.pile_remaining(), pool_size - 8);
+    }
+
+    #[test]
+    fn points_per_move_include_points_from_other_moves() {
+        let mut game = ScrabbleGame::with_default_config(&[letters("12+"), letters("3*")]).unwrap();
+        game.execute_command(&"place 12+;0;1;H".parse().unwrap())
+            .unwrap();
+        // P1 owns most of the extended term and gets its points
+        game.execute_command(&"place 3*;3;1;H".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(game.scores(), [12, 0]);
+        assert_eq!(game.points_per_move(), [12.0, 0.0]);
+    }
+
+    #[test]
+    fn scores_beyond_i32_are_kept_whole() {
+        let game = game_with_tiles(
+            &GameConfig::default(),
+            "9*",
+            &[
+                ((0, 0), ScrabbleLetter::Num9),
+                ((1, 0), ScrabbleLetter::Num9),
+                ((2, 0), ScrabbleLetter::Caret),
+            ],
+        );
+        let placement = Placement::new(&letters("9*"), &(3, 0), &Direction::Horizontal);
+
+        // 9^9 * 9 doesn't fit into an i32
+        assert_eq!(game.score_deltas(&placement).unwrap(), [(0, 3_486_784_401)]);
+        assert_eq!(game.potential(), 3_486_784_401);
+    }
+
+    #[test]
+    fn csv_tiles_have_to_be_allowed_by_the_rules() {
+        let config = GameConfig::builder()
+            .allowed_operators(&[ScrabbleLetter::Plus, ScrabbleLetter::Minus])
+            .build();
+        let mut game = ScrabbleGame::new(&config, &[letters("12+"), letters("")]).unwrap();
+        let path = temp_path("forbidden_tiles.csv");
+
+        for letter in [ScrabbleLetter::Dot, ScrabbleLetter::Equals] {
+            let mut board = GameBoard::new(10);
+            board.tiles[2][3].letter = letter;
+            std::fs::write(&path, board.to_csv()).unwrap();
+
+            assert!(matches!(
+                game.execute_command(&Command::ImportCsv(path.clone())),
+                Err(ScrabbleRuntimeError::InvalidCsv(_))
+            ));
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert!(game.board.occupied_cells().is_empty());
+    }
+}
@@ -1,7 +1,40 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 pub type PlayerID = usize;
 pub type Position = (isize, isize);
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+// far beyond any sensible board size, but small enough to never overflow when moving along a placement
+pub const MAX_COORDINATE: isize = u16::MAX as isize;
+// a board holds board_size² cells, so the size is kept far below MAX_COORDINATE
+pub const MAX_BOARD_SIZE: usize = 1000;
+pub const MAX_PLACEMENT_LENGTH: usize = 3;
+// rack size for dealt games if the config doesn't set one
+pub const DEFAULT_RACK_SIZE: usize = 7;
+
+pub const DEFAULT_TILE_DISTRIBUTION: [(ScrabbleLetter, usize); 13] = [
+    (ScrabbleLetter::Num0, 3),
+    (ScrabbleLetter::Num1, 5),
+    (ScrabbleLetter::Num2, 5),
+    (ScrabbleLetter::Num3, 5),
+    (ScrabbleLetter::Num4, 4),
+    (ScrabbleLetter::Num5, 4),
+    (ScrabbleLetter::Num6, 4),
+    (ScrabbleLetter::Num7, 3),
+    (ScrabbleLetter::Num8, 3),
+    (ScrabbleLetter::Num9, 3),
+    (ScrabbleLetter::Plus, 8),
+    (ScrabbleLetter::Minus, 6),
+    (ScrabbleLetter::Dot, 5),
+];
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Owner {
+    None,
+    Owning(PlayerID),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 #[repr(u8)]
 pub enum ScrabbleLetter {
     Num0,
@@ -17,6 +50,14 @@ pub enum ScrabbleLetter {
     Plus,
     Minus,
     Dot,
+    Slash,
+    Percent,
+    Caret,
+    LeftParenthesis,
+    RightParenthesis,
+    Equals,
+    // stands for whichever operator gives the term the highest value
+    OperatorJoker,
     Empty,
 }
 
@@ -36,10 +77,34 @@ impl ScrabbleLetter {
             '+' => Some(ScrabbleLetter::Plus),
             '-' => Some(ScrabbleLetter::Minus),
             '*' => Some(ScrabbleLetter::Dot),
+            '/' => Some(ScrabbleLetter::Slash),
+            '%' => Some(ScrabbleLetter::Percent),
+            '^' => Some(ScrabbleLetter::Caret),
+            '(' => Some(ScrabbleLetter::LeftParenthesis),
+            ')' => Some(ScrabbleLetter::RightParenthesis),
+            '=' => Some(ScrabbleLetter::Equals),
+            '#' => Some(ScrabbleLetter::OperatorJoker),
 
             _ => None,
         }
     }
+
+    pub fn is_digit(&self) -> bool {
+        (ScrabbleLetter::Num0..=ScrabbleLetter::Num9).contains(self)
+    }
+
+    pub fn is_operator(&self) -> bool {
+        matches!(
+            self,
+            ScrabbleLetter::Plus
+                | ScrabbleLetter::Minus
+                | ScrabbleLetter::Dot
+                | ScrabbleLetter::Slash
+                | ScrabbleLetter::Percent
+                | ScrabbleLetter::Caret
+                | ScrabbleLetter::OperatorJoker
+        )
+    }
 }
 
 impl std::fmt::Display for ScrabbleLetter {
@@ -61,12 +126,110 @@ impl std::fmt::Display for ScrabbleLetter {
                 ScrabbleLetter::Plus => '+',
                 ScrabbleLetter::Minus => '-',
                 ScrabbleLetter::Dot => '*',
+                ScrabbleLetter::Slash => '/',
+                ScrabbleLetter::Percent => '%',
+                ScrabbleLetter::Caret => '^',
+                ScrabbleLetter::LeftParenthesis => '(',
+                ScrabbleLetter::RightParenthesis => ')',
+                ScrabbleLetter::Equals => '=',
+                ScrabbleLetter::OperatorJoker => '#',
                 ScrabbleLetter::Empty => ' ',
             }
         )
     }
 }
 
+// glyphs a player writes instead of the usual letters, like the Arabic-Indic digits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    input_glyphs: BTreeMap<char, ScrabbleLetter>,
+    // shown instead of the letter, e.g. × for *, independent of what the player types
+    display_glyphs: BTreeMap<ScrabbleLetter, char>,
+}
+
+impl SymbolTable {
+    pub fn new(glyphs: &[(char, ScrabbleLetter)]) -> SymbolTable {
+        SymbolTable {
+            input_glyphs: glyphs.iter().copied().collect(),
+            display_glyphs: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_display(mut self, glyphs: &[(ScrabbleLetter, char)]) -> SymbolTable {
+        self.display_glyphs.extend(glyphs.iter().copied());
+        self
+    }
+
+    // glyphs missing from the table are read as usual
+    pub fn letter(&self, glyph: char) -> Option<ScrabbleLetter> {
+        self.input_glyphs
+            .get(&glyph)
+            .copied()
+            .or_else(|| ScrabbleLetter::from_char(glyph))
+    }
+
+    // letters missing from the table are shown as usual
+    pub fn glyph(&self, letter: ScrabbleLetter) -> String {
+        self.display_glyphs
+            .get(&letter)
+            .map(|glyph| glyph.to_string())
+            .unwrap_or_else(|| letter.to_string())
+    }
+}
+
+// written as glyph and letter pairs, display pairs follow after a ';' as letter and glyph,
+// e.g. "٠0,١1,٢2;*×"
+impl std::fmt::Display for SymbolTable {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pairs: Vec<String> = self
+            .input_glyphs
+            .iter()
+            .map(|(glyph, letter)| format!("{}{}", glyph, letter))
+            .collect();
+        write!(formatter, "{}", pairs.join(","))?;
+        if !self.display_glyphs.is_empty() {
+            let display_pairs: Vec<String> = self
+                .display_glyphs
+                .iter()
+                .map(|(letter, glyph)| format!("{}{}", letter, glyph))
+                .collect();
+            write!(formatter, ";{}", display_pairs.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SymbolTable {
+    type Err = ();
+
+    fn from_str(table_str: &str) -> Result<Self, Self::Err> {
+        let (input_str, display_str) = table_str.split_once(';').unwrap_or((table_str, ""));
+        let pairs = |pairs_str: &str| -> Result<Vec<(char, char)>, ()> {
+            pairs_str
+                .split(',')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| match pair.chars().collect::<Vec<char>>()[..] {
+                    [first, second] => Ok((first, second)),
+                    _ => Err(()),
+                })
+                .collect()
+        };
+        let input_glyphs = pairs(input_str)?
+            .into_iter()
+            .map(|(glyph, letter)| ScrabbleLetter::from_char(letter).map(|letter| (glyph, letter)))
+            .collect::<Option<Vec<(char, ScrabbleLetter)>>>()
+            .ok_or(())?;
+        let display_glyphs = pairs(display_str)?
+            .into_iter()
+            .map(|(letter, glyph)| ScrabbleLetter::from_char(letter).map(|letter| (letter, glyph)))
+            .collect::<Option<Vec<(ScrabbleLetter, char)>>>()
+            .ok_or(())?;
+
+        Ok(SymbolTable::new(&input_glyphs).with_display(&display_glyphs))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Direction {
     Horizontal,
@@ -89,7 +252,7 @@ impl Direction {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Placement {
     pub letters: Vec<ScrabbleLetter>,
     pub start_pos: Position,
@@ -98,21 +261,63 @@ pub struct Placement {
 
 impl Placement {
     pub fn new(
-        letters: &Vec<ScrabbleLetter>,
+        letters: &[ScrabbleLetter],
         start_pos: &Position,
         direction: &Direction,
     ) -> Placement {
         Placement {
-            letters: letters.clone(),
+            letters: letters.to_vec(),
             start_pos: *start_pos,
             direction: direction.clone(),
         }
     }
 }
 
+// uses the same letters;x;y;H|V form the place command accepts
+impl std::fmt::Display for Placement {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{};{};{};{}",
+            self.letters
+                .iter()
+                .map(ScrabbleLetter::to_string)
+                .collect::<String>(),
+            self.start_pos.0,
+            self.start_pos.1,
+            match self.direction {
+                Direction::Horizontal => "H",
+                Direction::Vertical => "V",
+            }
+        )
+    }
+}
+
+pub fn default_tile_pool() -> Vec<ScrabbleLetter> {
+    DEFAULT_TILE_DISTRIBUTION
+        .iter()
+        .flat_map(|(letter, count)| std::iter::repeat_n(*letter, *count))
+        .collect()
+}
+
 pub fn move_position(position: Position, offset: isize, direction: &Direction) -> Position {
     (
         position.0 + offset * direction.as_vec().0,
         position.1 + offset * direction.as_vec().1,
     )
 }
+
+pub fn checked_move_position(
+    position: Position,
+    offset: isize,
+    direction: &Direction,
+) -> Option<Position> {
+    Some((
+        position
+            .0
+            .checked_add(offset.checked_mul(direction.as_vec().0)?)?,
+        position
+            .1
+            .checked_add(offset.checked_mul(direction.as_vec().1)?)?,
+    ))
+}
@@ -1,31 +1,325 @@
-use crate::scrabble_base_types::ScrabbleLetter;
+use std::collections::HashMap;
+use std::str::FromStr;
 
-#[derive(Debug, Eq, PartialEq)]
+use crate::game_config::{Notation, OwnershipRule};
+use crate::scrabble_base_types::{Owner, ScrabbleLetter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermEvalError {
+    InvalidCharacter(char),
+    InvalidTerm(String),
+}
+
+impl std::fmt::Display for TermEvalError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TermEvalError::InvalidCharacter(character) => {
+                write!(formatter, "Error: '{}' is not a valid letter!", character)
+            }
+            TermEvalError::InvalidTerm(cause) => write!(formatter, "Error: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for TermEvalError {}
+
+// evaluates a string of letters like "12+3*" without a board
+pub fn eval_expression(expression: &str) -> Result<i64, TermEvalError> {
+    eval_expression_with(expression, &EvaluationRules::default())
+}
+
+// like eval_expression, but in the notation of the rules, e.g. "12+3" with infix rules
+pub fn eval_expression_with(
+    expression: &str,
+    rules: &EvaluationRules,
+) -> Result<i64, TermEvalError> {
+    expression
+        .parse::<Term>()?
+        .evaluate_with(rules)
+        .map_err(TermEvalError::InvalidTerm)
+}
+
+// switches changing how the letters of a term are read
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationRules {
+    // adjacent digits form one number, so "12" is twelve instead of two operands
+    pub fold_digits: bool,
+    // infix terms always fold their digits, only parentheses can separate two numbers
+    pub notation: Notation,
+    // in infix terms a number or parenthesis right before another one multiplies, "2(3+4)" is 14
+    // while "23" is still twenty-three, digits fold before anything is multiplied
+    pub implicit_multiplication: bool,
+    // terms have to be equations like "2+3=5" and are worth the value of both sides
+    pub equations: bool,
+    // every operator is allowed if there is no list
+    pub allowed_operators: Option<Vec<ScrabbleLetter>>,
+}
+
+impl EvaluationRules {
+    fn allows(&self, operator: &ScrabbleLetter) -> bool {
+        self.allowed_operators
+            .as_ref()
+            .is_none_or(|allowed_operators| allowed_operators.contains(operator))
+    }
+
+    // whether the letter may appear in a term at all, wherever it is
+    pub fn check_letter(&self, letter: &ScrabbleLetter) -> Result<(), String> {
+        match letter {
+            operator if operator.is_operator() && !self.allows(operator) => Err(format!(
+                "The operator '{}' is not allowed in this game!",
+                operator
+            )),
+            ScrabbleLetter::Equals if !self.equations => {
+                Err("The = is only allowed in equations!".to_string())
+            }
+            ScrabbleLetter::LeftParenthesis | ScrabbleLetter::RightParenthesis
+                if self.notation != Notation::Infix =>
+            {
+                Err("Parentheses are only allowed in infix terms!".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Number(i64),
+    Operator(ScrabbleLetter),
+    // a minus without a left operand in infix terms
+    Negation,
+    LeftParenthesis,
+    RightParenthesis,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(formatter, "{}", number),
+            Token::Operator(operator) => write!(formatter, "{}", operator),
+            Token::Negation => write!(formatter, "neg"),
+            Token::LeftParenthesis => write!(formatter, "("),
+            Token::RightParenthesis => write!(formatter, ")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Term {
     tokens: Vec<ScrabbleLetter>,
 }
 
+impl FromStr for Term {
+    type Err = TermEvalError;
+
+    fn from_str(term_str: &str) -> Result<Self, Self::Err> {
+        let letters = term_str
+            .chars()
+            .map(|character| {
+                ScrabbleLetter::from_char(character)
+                    .ok_or(TermEvalError::InvalidCharacter(character))
+            })
+            .collect::<Result<Vec<ScrabbleLetter>, TermEvalError>>()?;
+
+        Ok(Term::new(&letters))
+    }
+}
+
 impl Term {
-    pub fn new(letters: &Vec<ScrabbleLetter>) -> Term {
+    pub fn new(letters: &[ScrabbleLetter]) -> Term {
         Term {
-            tokens: letters.clone(),
+            tokens: letters.to_vec(),
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
     pub fn is_singleton(&self) -> bool {
-        return self.tokens.len() == 1;
+        self.tokens.len() == 1
+    }
+
+    pub fn reversed(&self) -> Term {
+        Term {
+            tokens: self.tokens.iter().rev().copied().collect(),
+        }
+    }
+
+    pub fn count(&self, letter: ScrabbleLetter) -> usize {
+        self.tokens.iter().filter(|token| **token == letter).count()
+    }
+
+    pub fn max_operand(&self, rules: &EvaluationRules) -> Option<i64> {
+        self.tokenize(rules)
+            .ok()?
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Number(number) => Some(number),
+                _ => None,
+            })
+            .max()
+    }
+
+    pub fn tile_sum(&self) -> i32 {
+        self.tokens
+            .iter()
+            .filter(|token| token.is_digit())
+            .map(|digit| *digit as i32)
+            .sum()
+    }
+
+    pub fn evaluate(&self) -> Result<i64, String> {
+        self.evaluate_with(&EvaluationRules::default())
+    }
+
+    pub fn evaluate_infix(&self) -> Result<i64, String> {
+        self.evaluate_with(&EvaluationRules {
+            notation: Notation::Infix,
+            ..EvaluationRules::default()
+        })
+    }
+
+    pub fn evaluate_equation(&self) -> Result<i64, String> {
+        self.evaluate_with(&EvaluationRules {
+            equations: true,
+            ..EvaluationRules::default()
+        })
+    }
+
+    pub fn evaluate_with(&self, rules: &EvaluationRules) -> Result<i64, String> {
+        for operator in self.tokens.iter().filter(|letter| letter.is_operator()) {
+            rules.check_letter(operator)?;
+        }
+        if self.count(ScrabbleLetter::OperatorJoker) == 0 {
+            self.evaluate_resolved(rules)
+        } else {
+            self.resolve_jokers_with(rules)?.evaluate_resolved(rules)
+        }
     }
 
-    pub fn evaluate(&self) -> Result<i32, String> {
-        let mut operand_stack: Vec<i32> = Vec::new();
+    fn evaluate_resolved(&self, rules: &EvaluationRules) -> Result<i64, String> {
+        if !rules.equations {
+            return self.evaluate_tokens(rules);
+        }
+        let [left, right] = self.equation_sides()?;
+        let (left_value, right_value) =
+            (left.evaluate_tokens(rules)?, right.evaluate_tokens(rules)?);
+        if left_value != right_value {
+            return Err("Equation does not balance!".to_string());
+        }
+
+        Ok(left_value)
+    }
+
+    fn equation_sides(&self) -> Result<[Term; 2], String> {
+        match self.count(ScrabbleLetter::Equals) {
+            0 => Err("The term is not an equation!".to_string()),
+            1 => {
+                let mut sides = self
+                    .tokens
+                    .split(|letter| *letter == ScrabbleLetter::Equals)
+                    .map(Term::new);
+                Ok([
+                    sides.next().expect("BUG: equation without left side!"),
+                    sides.next().expect("BUG: equation without right side!"),
+                ])
+            }
+            _ => Err("An equation can only contain one =!".to_string()),
+        }
+    }
+
+    pub fn resolve_jokers(&self) -> Result<Term, String> {
+        self.resolve_jokers_with(&EvaluationRules::default())
+    }
+
+    // replaces every operator joker so that the term gets the highest possible value
+    pub fn resolve_jokers_with(&self, rules: &EvaluationRules) -> Result<Term, String> {
+        let mut resolutions: Vec<Vec<ScrabbleLetter>> = vec![Vec::new()];
         for token in &self.tokens {
-            match token {
-                ScrabbleLetter::Plus => binary_operator(|f, s| f + s, "+", &mut operand_stack)?,
-                ScrabbleLetter::Minus => binary_operator(|f, s| f - s, "-", &mut operand_stack)?,
-                ScrabbleLetter::Dot => binary_operator(|f, s| f * s, "*", &mut operand_stack)?,
+            let choices: Vec<ScrabbleLetter> = if *token == ScrabbleLetter::OperatorJoker {
+                JOKER_OPERATORS
+                    .into_iter()
+                    .filter(|operator| rules.allows(operator))
+                    .collect()
+            } else {
+                vec![*token]
+            };
+            resolutions = resolutions
+                .into_iter()
+                .flat_map(|prefix| {
+                    choices.iter().map(move |choice| {
+                        let mut resolution = prefix.clone();
+                        resolution.push(*choice);
+                        resolution
+                    })
+                })
+                .collect();
+        }
+
+        resolutions
+            .into_iter()
+            .map(|tokens| Term { tokens })
+            .filter_map(|term| {
+                term.evaluate_resolved(rules)
+                    .ok()
+                    .map(|value| (term, value))
+            })
+            .min_by_key(|(_, value)| std::cmp::Reverse(*value))
+            .map(|(term, _)| term)
+            .ok_or("No operator for the joker makes the term valid!".to_string())
+    }
+
+    // groups the letters into operands and operators, without folding every digit is its own operand
+    pub fn tokenize(&self, rules: &EvaluationRules) -> Result<Vec<Token>, String> {
+        let mut tokens = Vec::new();
+        let mut letters = self.tokens.iter().peekable();
+        let fold_digits = rules.fold_digits || rules.notation == Notation::Infix;
+
+        while let Some(letter) = letters.next() {
+            match letter {
                 ScrabbleLetter::Empty => return Err("Found empty token in term!".to_string()),
-                num => operand_stack.push(*num as i32),
+                ScrabbleLetter::Equals => {
+                    return Err("The = is only allowed in equations!".to_string())
+                }
+                ScrabbleLetter::LeftParenthesis => tokens.push(Token::LeftParenthesis),
+                ScrabbleLetter::RightParenthesis => tokens.push(Token::RightParenthesis),
+                operator if operator.is_operator() => tokens.push(Token::Operator(*operator)),
+                _ => {}
+            }
+            if !letter.is_digit() {
+                continue;
+            }
+            let mut digits = vec![*letter];
+            while let Some(digit) = letters.next_if(|next| fold_digits && next.is_digit()) {
+                digits.push(*digit);
+            }
+            tokens.push(Token::Number(fold_number(&digits)?));
+        }
+
+        Ok(tokens)
+    }
+
+    // the tokens in the order the stack machine consumes them
+    fn postfix_tokens(&self, rules: &EvaluationRules) -> Result<Vec<Token>, String> {
+        let tokens = self.tokenize(rules)?;
+        match rules.notation {
+            Notation::Postfix => Ok(negate_leading_minus(tokens)),
+            Notation::Infix if rules.implicit_multiplication => {
+                infix_to_postfix(&insert_implicit_multiplication(tokens))
             }
+            Notation::Infix => infix_to_postfix(&tokens),
+        }
+    }
+
+    fn evaluate_tokens(&self, rules: &EvaluationRules) -> Result<i64, String> {
+        let mut operand_stack: Vec<i64> = Vec::new();
+        for token in self.postfix_tokens(rules)? {
+            apply_token(&token, &mut operand_stack)?;
         }
 
         if operand_stack.len() > 1 {
@@ -35,16 +329,453 @@ impl Term {
             .pop()
             .ok_or("Empty operand stack at the end of evaluation!".to_string())
     }
+
+    // distributes the score of the term among the owners of its tiles, `owners` holds one entry per tile
+    pub fn score_contributions(
+        &self,
+        score: i64,
+        owners: &[Owner],
+        rule: OwnershipRule,
+    ) -> HashMap<Owner, i64> {
+        assert_eq!(owners.len(), self.tokens.len());
+
+        let mut tile_counts: HashMap<Owner, usize> = HashMap::new();
+        for owner in owners {
+            *tile_counts.entry(*owner).or_insert(0) += 1;
+        }
+
+        match rule {
+            OwnershipRule::Majority => {
+                let mut frequencies: Vec<(Owner, usize)> = tile_counts.into_iter().collect();
+                frequencies.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                let owner = if frequencies.len() >= 2 && frequencies[0].1 == frequencies[1].1 {
+                    Owner::None
+                } else {
+                    frequencies[0].0
+                };
+                HashMap::from([(owner, score)])
+            }
+            OwnershipRule::Split => tile_counts
+                .into_iter()
+                // the share is never larger than the score, only the product needs the wider type
+                .map(|(owner, count)| {
+                    let share = score as i128 * count as i128 / owners.len() as i128;
+                    (owner, share as i64)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_rpn_string(&self) -> String {
+        self.tokens
+            .iter()
+            .map(ScrabbleLetter::to_string)
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    // terms that can't be read as infix, like 12 with its unused operand, are shown as RPN instead
+    pub fn to_infix_string(&self) -> String {
+        self.to_infix_string_with(&EvaluationRules::default())
+            .unwrap_or_else(|_| self.to_rpn_string())
+    }
+
+    // infix terms are shown as they are, postfix terms get the parentheses their order needs
+    pub fn to_infix_string_with(&self, rules: &EvaluationRules) -> Result<String, String> {
+        if rules.equations {
+            let side_rules = EvaluationRules {
+                equations: false,
+                ..rules.clone()
+            };
+            let [left, right] = self.equation_sides()?;
+            return Ok(format!(
+                "{}={}",
+                left.to_infix_string_with(&side_rules)?,
+                right.to_infix_string_with(&side_rules)?
+            ));
+        }
+        if self.count(ScrabbleLetter::OperatorJoker) > 0 {
+            return self.resolve_jokers_with(rules)?.to_infix_string_with(rules);
+        }
+        let tokens = self.tokenize(rules)?;
+        if rules.notation == Notation::Infix {
+            return Ok(tokens.iter().map(Token::to_string).collect());
+        }
+        let tokens = negate_leading_minus(tokens);
+        // rendered sub-expressions together with the precedence of their outermost operator
+        let mut expression_stack: Vec<(String, u8)> = Vec::new();
+
+        for token in &tokens {
+            let token = match token {
+                Token::Number(number) => {
+                    expression_stack.push((number.to_string(), OPERAND_PRECEDENCE));
+                    continue;
+                }
+                Token::Operator(operator) => operator,
+                Token::LeftParenthesis | Token::RightParenthesis => {
+                    return Err("Parentheses are only allowed in infix terms!".to_string())
+                }
+                Token::Negation => {
+                    let Some(operand) = expression_stack.pop() else {
+                        return Err("The negation expects an argument!".to_string());
+                    };
+                    let negated = format!(
+                        "-{}",
+                        parenthesize(operand.0, operand.1 < NEGATION_PRECEDENCE)
+                    );
+                    expression_stack.push((negated, NEGATION_PRECEDENCE));
+                    continue;
+                }
+            };
+
+            let (Some(second), Some(first)) = (expression_stack.pop(), expression_stack.pop())
+            else {
+                return Err(format!("The Operator {} expects 2 arguments!", token));
+            };
+            let precedence = operator_precedence(token);
+            let needs_left_parentheses =
+                first.1 < precedence || (first.1 == precedence && is_right_associative(token));
+            let needs_right_parentheses = second.1 < precedence
+                || (second.1 == precedence
+                    && !is_associative(token)
+                    && !is_right_associative(token));
+
+            expression_stack.push((
+                format!(
+                    "{}{}{}",
+                    parenthesize(first.0, needs_left_parentheses),
+                    token,
+                    parenthesize(second.0, needs_right_parentheses)
+                ),
+                precedence,
+            ));
+        }
+
+        if expression_stack.len() > 1 {
+            return Err("Unused arguments are left on the stack!".to_string());
+        }
+        expression_stack
+            .pop()
+            .map(|(expression, _)| expression)
+            .ok_or("Empty operand stack at the end of evaluation!".to_string())
+    }
+
+    // one line per token with the operand stack after it, ending at the first failing token
+    pub fn evaluation_trace(&self, rules: &EvaluationRules) -> Vec<String> {
+        if rules.equations {
+            return match self.equation_sides() {
+                Ok(sides) => {
+                    let side_rules = EvaluationRules {
+                        equations: false,
+                        ..rules.clone()
+                    };
+                    sides
+                        .map(|side| side.evaluation_trace(&side_rules))
+                        .join(&"=".to_string())
+                }
+                Err(err) => vec![err],
+            };
+        }
+        let mut trace = Vec::new();
+        let mut operand_stack: Vec<i64> = Vec::new();
+        let tokens = match self.postfix_tokens(rules) {
+            Ok(tokens) => tokens,
+            Err(err) => return vec![err],
+        };
+
+        for token in &tokens {
+            match apply_token(token, &mut operand_stack) {
+                Ok(()) => trace.push(format!("{} -> {:?}", token, operand_stack)),
+                Err(err) => {
+                    trace.push(format!("{} -> {}", token, err));
+                    return trace;
+                }
+            }
+        }
+        if operand_stack.len() != 1 {
+            trace.push(format!("end -> {:?}", operand_stack));
+        }
+
+        trace
+    }
+
+    pub fn subexpressions(&self) -> Vec<(Term, i64)> {
+        if self.count(ScrabbleLetter::OperatorJoker) > 0 {
+            return self
+                .resolve_jokers()
+                .map(|resolved| resolved.subexpressions())
+                .unwrap_or_default();
+        }
+        let mut steps = Vec::new();
+        let mut operand_stack: Vec<i64> = Vec::new();
+        // index of the first token of every sub-expression currently on the operand stack
+        let mut start_stack: Vec<usize> = Vec::new();
+        // without folding every letter is one token, so token and letter indices match, a leading
+        // negation swaps places with its number and the pair still spans the same two letters
+        let Ok(tokens) = self.postfix_tokens(&EvaluationRules::default()) else {
+            return steps;
+        };
+
+        for (index, token) in tokens.iter().enumerate() {
+            let depth = operand_stack.len();
+            if apply_token(token, &mut operand_stack).is_err() {
+                break;
+            }
+
+            if let Token::Number(_) = token {
+                start_stack.push(index);
+                continue;
+            }
+            // a negation keeps the sub-expression it applies to, binary operators merge two
+            if operand_stack.len() < depth {
+                start_stack.pop();
+            }
+            // operators combine the two topmost sub-expressions, which are adjacent in RPN
+            let start = *start_stack.last().expect("BUG: operator without operands!");
+            let value = *operand_stack.last().expect("BUG: operator without result!");
+            steps.push((Term::new(&self.tokens[start..=index]), value));
+        }
+
+        steps
+    }
+}
+
+const OPERAND_PRECEDENCE: u8 = 4;
+// below the power, so -2^2 is -(2^2)
+const NEGATION_PRECEDENCE: u8 = 3;
+const JOKER_OPERATORS: [ScrabbleLetter; 6] = [
+    ScrabbleLetter::Plus,
+    ScrabbleLetter::Minus,
+    ScrabbleLetter::Dot,
+    ScrabbleLetter::Slash,
+    ScrabbleLetter::Percent,
+    ScrabbleLetter::Caret,
+];
+
+fn operator_precedence(operator: &ScrabbleLetter) -> u8 {
+    match operator {
+        ScrabbleLetter::Plus | ScrabbleLetter::Minus => 1,
+        ScrabbleLetter::Dot | ScrabbleLetter::Slash | ScrabbleLetter::Percent => 2,
+        ScrabbleLetter::Caret => 3,
+        _ => OPERAND_PRECEDENCE,
+    }
+}
+
+fn is_associative(operator: &ScrabbleLetter) -> bool {
+    matches!(operator, ScrabbleLetter::Plus | ScrabbleLetter::Dot)
+}
+
+// 2^3^2 is read as 2^(3^2)
+fn is_right_associative(operator: &ScrabbleLetter) -> bool {
+    *operator == ScrabbleLetter::Caret
+}
+
+fn parenthesize(expression: String, needs_parentheses: bool) -> String {
+    if needs_parentheses {
+        format!("({})", expression)
+    } else {
+        expression
+    }
+}
+
+fn apply_token(token: &Token, operand_stack: &mut Vec<i64>) -> Result<(), String> {
+    let operator = match token {
+        Token::Number(number) => {
+            operand_stack.push(*number);
+            return Ok(());
+        }
+        Token::Operator(operator) => operator,
+        Token::Negation => return negate(operand_stack),
+        Token::LeftParenthesis | Token::RightParenthesis => {
+            return Err("Parentheses are only allowed in infix terms!".to_string())
+        }
+    };
+
+    match operator {
+        ScrabbleLetter::Plus => binary_operator(
+            |f, s| f.checked_add(s).ok_or_else(overflow_err),
+            "+",
+            operand_stack,
+        ),
+        ScrabbleLetter::Minus => binary_operator(
+            |f, s| f.checked_sub(s).ok_or_else(overflow_err),
+            "-",
+            operand_stack,
+        ),
+        ScrabbleLetter::Dot => binary_operator(
+            |f, s| f.checked_mul(s).ok_or_else(overflow_err),
+            "*",
+            operand_stack,
+        ),
+        ScrabbleLetter::Slash => binary_operator(
+            |f, s| {
+                if s == 0 {
+                    Err("Division by zero!".to_string())
+                } else {
+                    f.checked_div(s).ok_or_else(overflow_err)
+                }
+            },
+            "/",
+            operand_stack,
+        ),
+        // the remainder is truncated like in Rust and takes the sign of the dividend, -7 % 3 is -1
+        ScrabbleLetter::Percent => binary_operator(
+            |f, s| {
+                if s == 0 {
+                    Err("Modulo by zero!".to_string())
+                } else {
+                    f.checked_rem(s).ok_or_else(overflow_err)
+                }
+            },
+            "%",
+            operand_stack,
+        ),
+        // the exponent is the topmost number, negative ones would give fractions
+        ScrabbleLetter::Caret => binary_operator(
+            |f, s| {
+                let exponent =
+                    u32::try_from(s).map_err(|_| format!("Negative exponent {} in power!", s))?;
+                f.checked_pow(exponent).ok_or_else(overflow_err)
+            },
+            "^",
+            operand_stack,
+        ),
+        ScrabbleLetter::OperatorJoker => Err("Found unresolved joker in term!".to_string()),
+        _ => unreachable!("BUG: only operators become operator tokens!"),
+    }
+}
+
+fn insert_implicit_multiplication(tokens: Vec<Token>) -> Vec<Token> {
+    let mut multiplied = Vec::new();
+    for token in tokens {
+        let ends_operand = matches!(
+            multiplied.last(),
+            Some(Token::Number(_) | Token::RightParenthesis)
+        );
+        if ends_operand && matches!(token, Token::Number(_) | Token::LeftParenthesis) {
+            multiplied.push(Token::Operator(ScrabbleLetter::Dot));
+        }
+        multiplied.push(token);
+    }
+
+    multiplied
+}
+
+// shunting-yard, operators of a higher precedence bind first, so "2+3*4" is 2+(3*4)
+fn infix_to_postfix(tokens: &[Token]) -> Result<Vec<Token>, String> {
+    let mut postfix = Vec::new();
+    // operators and opening parentheses that still wait for their right side
+    let mut pending: Vec<Token> = Vec::new();
+    let binds_before = |pending: &Token, operator: &ScrabbleLetter| {
+        let pending_precedence = match pending {
+            Token::Operator(pending) => operator_precedence(pending),
+            Token::Negation => NEGATION_PRECEDENCE,
+            _ => return false,
+        };
+        let precedence = operator_precedence(operator);
+        pending_precedence > precedence
+            || (pending_precedence == precedence && !is_right_associative(operator))
+    };
+    // numbers and operators have to alternate, starting and ending with a number
+    let mut expects_number = true;
+    let mut previous: Option<&Token> = None;
+
+    for token in tokens {
+        match (token, expects_number) {
+            (Token::Number(_), true) => {
+                postfix.push(*token);
+                expects_number = false;
+            }
+            (Token::LeftParenthesis, true) => pending.push(*token),
+            (Token::Operator(ScrabbleLetter::Minus), true) => pending.push(Token::Negation),
+            (Token::Operator(operator), false) => {
+                while let Some(stacked) = pending.pop_if(|stacked| binds_before(stacked, operator))
+                {
+                    postfix.push(stacked);
+                }
+                pending.push(*token);
+                expects_number = true;
+            }
+            (Token::RightParenthesis, false) => loop {
+                match pending.pop() {
+                    Some(Token::LeftParenthesis) => break,
+                    Some(operator) => postfix.push(operator),
+                    None => return Err("The term has an unopened parenthesis!".to_string()),
+                }
+            },
+            _ => {
+                return Err(match previous {
+                    Some(previous) => {
+                        format!("{} can't follow {} in an infix term!", token, previous)
+                    }
+                    None => format!("The term can't start with {}!", token),
+                })
+            }
+        }
+        previous = Some(token);
+    }
+    if let Some(previous) = previous.filter(|_| expects_number) {
+        return Err(format!("The term can't end with {}!", previous));
+    }
+    while let Some(token) = pending.pop() {
+        if token == Token::LeftParenthesis {
+            return Err("The term has an unclosed parenthesis!".to_string());
+        }
+        postfix.push(token);
+    }
+
+    Ok(postfix)
+}
+
+// a postfix term starting with a minus has nothing on the stack to subtract from, so the minus
+// negates the number after it, -53- is (-5)-3 and 5- stays invalid
+fn negate_leading_minus(mut tokens: Vec<Token>) -> Vec<Token> {
+    if let [Token::Operator(ScrabbleLetter::Minus), Token::Number(_), ..] = tokens[..] {
+        tokens.swap(0, 1);
+        tokens[1] = Token::Negation;
+    }
+    tokens
+}
+
+fn fold_number(digits: &[ScrabbleLetter]) -> Result<i64, String> {
+    if digits.len() > 1 && digits[0] == ScrabbleLetter::Num0 {
+        return Err(format!(
+            "The number {} has a leading zero!",
+            digits
+                .iter()
+                .map(ScrabbleLetter::to_string)
+                .collect::<String>()
+        ));
+    }
+    digits.iter().try_fold(0i64, |number, digit| {
+        number
+            .checked_mul(10)
+            .and_then(|number| number.checked_add(*digit as i64))
+            .ok_or_else(overflow_err)
+    })
+}
+
+fn negate(operand_stack: &mut [i64]) -> Result<(), String> {
+    let operand = operand_stack
+        .last_mut()
+        .ok_or("The negation expects an argument!".to_string())?;
+    *operand = operand.checked_neg().ok_or_else(overflow_err)?;
+    Ok(())
+}
+
+fn overflow_err() -> String {
+    "Arithmetic overflow in term!".to_string()
 }
 
 fn binary_operator(
-    operator: impl Fn(i32, i32) -> i32,
+    operator: impl Fn(i64, i64) -> Result<i64, String>,
     operator_name: &str,
-    operand_stack: &mut Vec<i32>,
+    operand_stack: &mut Vec<i64>,
 ) -> Result<(), String> {
     if let [.., first, second] = operand_stack[..] {
+        let result = operator(first, second)?;
         operand_stack.truncate(operand_stack.len() - 2);
-        operand_stack.push(operator(first, second));
+        operand_stack.push(result);
         Ok(())
     } else {
         Err(format!(
@@ -54,3 +785,346 @@ fn binary_operator(
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term_str: &str) -> Term {
+        let letters: Vec<ScrabbleLetter> = term_str
+            .chars()
+            .map(|glyph| ScrabbleLetter::from_char(glyph).expect("invalid letter in test"))
+            .collect();
+        Term::new(&letters)
+    }
+
+    #[test]
+    fn subexpressions_follow_the_evaluation_order() {
+        assert_eq!(
+            term("12+3*").subexpressions(),
+            [(term("12+"), 3), (term("12+3*"), 9)]
+        );
+    }
+
+    #[test]
+    fn terms_render_as_rpn_and_infix() {
+        let term = term("12+34-*");
+
+        assert_eq!(term.to_rpn_string(), "1 2 + 3 4 - *");
+        assert_eq!(term.to_infix_string(), "(1+2)*(3-4)");
+    }
+
+    #[test]
+    fn term_scores_go_to_the_majority_or_are_split() {
+        let term = term("12+3*");
+        let owners = [
+            Owner::Owning(0),
+            Owner::Owning(0),
+            Owner::Owning(1),
+            Owner::Owning(0),
+            Owner::Owning(1),
+        ];
+
+        assert_eq!(
+            term.score_contributions(10, &owners, OwnershipRule::Majority),
+            HashMap::from([(Owner::Owning(0), 10)])
+        );
+        assert_eq!(
+            term.score_contributions(10, &owners, OwnershipRule::Split),
+            HashMap::from([(Owner::Owning(0), 6), (Owner::Owning(1), 4)])
+        );
+    }
+
+    #[test]
+    fn expressions_evaluate_without_a_board() {
+        assert_eq!(eval_expression("12+"), Ok(3));
+        assert_eq!(eval_expression("93-2*"), Ok(12));
+        assert_eq!(
+            eval_expression("12a+"),
+            Err(TermEvalError::InvalidCharacter('a'))
+        );
+        assert!(matches!(
+            eval_expression("1+"),
+            Err(TermEvalError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn division_and_division_by_zero() {
+        assert_eq!(eval_expression("62/"), Ok(3));
+        assert_eq!(eval_expression("72/"), Ok(3));
+        assert!(matches!(
+            eval_expression("60/"),
+            Err(TermEvalError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn failing_operators_keep_their_operands() {
+        let mut operand_stack = vec![4, 0];
+
+        assert_eq!(
+            apply_token(&Token::Operator(ScrabbleLetter::Slash), &mut operand_stack),
+            Err("Division by zero!".to_string())
+        );
+        assert_eq!(operand_stack, [4, 0]);
+    }
+
+    #[test]
+    fn modulo_truncates_like_rust() {
+        assert_eq!(eval_expression("73%"), Ok(1));
+        // 0 - 7 is a negative dividend, the remainder keeps its sign
+        assert_eq!(eval_expression("07-3%"), Ok(-1));
+        assert!(matches!(
+            eval_expression("70%"),
+            Err(TermEvalError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn powers_take_the_topmost_number_as_the_exponent() {
+        assert_eq!(eval_expression("23^"), Ok(8));
+        assert_eq!(eval_expression("32^"), Ok(9));
+        assert_eq!(
+            eval_expression("203-^"),
+            Err(TermEvalError::InvalidTerm(
+                "Negative exponent -3 in power!".to_string()
+            ))
+        );
+        assert_eq!(
+            eval_expression("29^9^"),
+            Err(TermEvalError::InvalidTerm(
+                "Arithmetic overflow in term!".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn powers_render_right_associative() {
+        assert_eq!(term("232^^").to_infix_string(), "2^3^2");
+        assert_eq!(term("23^2^").to_infix_string(), "(2^3)^2");
+    }
+
+    #[test]
+    fn terms_evaluate_beyond_i32_and_report_i64_overflow() {
+        // 9^11 and 9^20, the first one fits into an i64, the second one doesn't
+        assert_eq!(eval_expression("99*9*9*9*9*9*9*9*9*9*"), Ok(31_381_059_609));
+        assert_eq!(
+            eval_expression("945*^"),
+            Err(TermEvalError::InvalidTerm(
+                "Arithmetic overflow in term!".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn folded_digits_form_one_operand() {
+        let folding = EvaluationRules {
+            fold_digits: true,
+            ..EvaluationRules::default()
+        };
+
+        assert_eq!(term("12+").evaluate(), Ok(3));
+        assert_eq!(
+            term("12+").tokenize(&folding),
+            Ok(vec![
+                Token::Number(12),
+                Token::Operator(ScrabbleLetter::Plus)
+            ])
+        );
+        assert!(term("12+").evaluate_with(&folding).is_err());
+        assert_eq!(term("120").evaluate_with(&folding), Ok(120));
+    }
+
+    #[test]
+    fn folded_numbers_may_not_start_with_a_zero() {
+        let folding = EvaluationRules {
+            fold_digits: true,
+            ..EvaluationRules::default()
+        };
+
+        assert!(term("05").tokenize(&folding).is_err());
+        assert_eq!(term("0").evaluate_with(&folding), Ok(0));
+        // without folding the 0 and the 5 are two operands
+        assert_eq!(term("05+").evaluate(), Ok(5));
+    }
+
+    #[test]
+    fn infix_terms_alternate_numbers_and_operators() {
+        assert_eq!(term("12+3").evaluate_infix(), Ok(15));
+        assert_eq!(
+            term("2+").evaluate_infix(),
+            Err("The term can't end with +!".to_string())
+        );
+        assert_eq!(
+            term("+2").evaluate_infix(),
+            Err("The term can't start with +!".to_string())
+        );
+    }
+
+    #[test]
+    fn infix_operators_bind_by_precedence() {
+        assert_eq!(term("2+3*4").evaluate_infix(), Ok(14));
+        assert_eq!(term("2*3+4").evaluate_infix(), Ok(10));
+        assert_eq!(term("8-3-2").evaluate_infix(), Ok(3));
+        // powers are right associative, 2^(3^2) instead of (2^3)^2
+        assert_eq!(term("2^3^2").evaluate_infix(), Ok(512));
+    }
+
+    #[test]
+    fn parentheses_group_infix_terms() {
+        assert_eq!(term("(2+3)*4").evaluate_infix(), Ok(20));
+        assert_eq!(term("2+3*4").evaluate_infix(), Ok(14));
+        assert_eq!(
+            term("(2+3").evaluate_infix(),
+            Err("The term has an unclosed parenthesis!".to_string())
+        );
+    }
+
+    #[test]
+    fn a_minus_without_a_left_operand_negates() {
+        assert_eq!(term("-5+8").evaluate_infix(), Ok(3));
+        assert_eq!(term("-2^2").evaluate_infix(), Ok(-4));
+        assert_eq!(term("2*-3").evaluate_infix(), Ok(-6));
+        // postfix terms only negate with an empty stack, that is a leading minus
+        assert_eq!(term("-5").evaluate(), Ok(-5));
+        assert_eq!(term("-53-").evaluate(), Ok(-8));
+        assert_eq!(term("53-").evaluate(), Ok(2));
+        assert!(term("5-").evaluate().is_err());
+        assert!(term("5-3+").evaluate().is_err());
+        assert_eq!(term("-53-").to_infix_string(), "-5-3");
+        assert_eq!(
+            term("-53-").subexpressions(),
+            [(term("-5"), -5), (term("-53-"), -8)]
+        );
+    }
+
+    #[test]
+    fn equations_are_worth_the_value_of_both_sides() {
+        let infix_equations = EvaluationRules {
+            notation: Notation::Infix,
+            equations: true,
+            ..EvaluationRules::default()
+        };
+
+        assert_eq!(term("2+3=5").evaluate_with(&infix_equations), Ok(5));
+        assert_eq!(term("23+=5").evaluate_equation(), Ok(5));
+        assert_eq!(
+            term("23+=6").evaluate_equation(),
+            Err("Equation does not balance!".to_string())
+        );
+        assert_eq!(
+            term("5=5=5").evaluate_equation(),
+            Err("An equation can only contain one =!".to_string())
+        );
+        assert_eq!(
+            term("23+").evaluate_equation(),
+            Err("The term is not an equation!".to_string())
+        );
+    }
+
+    #[test]
+    fn the_equals_tile_is_only_allowed_in_equations() {
+        assert_eq!(
+            term("23+=5").evaluate(),
+            Err("The = is only allowed in equations!".to_string())
+        );
+    }
+
+    #[test]
+    fn forbidden_operators_fail_and_jokers_avoid_them() {
+        let without_plus = EvaluationRules {
+            allowed_operators: Some(vec![
+                ScrabbleLetter::Minus,
+                ScrabbleLetter::Dot,
+                ScrabbleLetter::OperatorJoker,
+            ]),
+            ..EvaluationRules::default()
+        };
+
+        assert_eq!(
+            term("12+").evaluate_with(&without_plus),
+            Err("The operator '+' is not allowed in this game!".to_string())
+        );
+        // 2 + 1 would be the best choice for the joker
+        assert_eq!(term("21#").evaluate(), Ok(3));
+        assert_eq!(term("21#").evaluate_with(&without_plus), Ok(2));
+    }
+
+    #[test]
+    fn infix_strings_group_folded_digits() {
+        let folding = EvaluationRules {
+            fold_digits: true,
+            ..EvaluationRules::default()
+        };
+        let infix = EvaluationRules {
+            notation: Notation::Infix,
+            ..EvaluationRules::default()
+        };
+
+        // without folding every digit is its own number
+        assert_eq!(term("12+3*").to_infix_string(), "(1+2)*3");
+        assert_eq!(term("12").to_infix_string(), "1 2");
+        assert_eq!(
+            term("120").to_infix_string_with(&folding),
+            Ok("120".to_string())
+        );
+        assert_eq!(
+            term("-12").to_infix_string_with(&folding),
+            Ok("-12".to_string())
+        );
+        assert_eq!(
+            term("12+34*5").to_infix_string_with(&infix),
+            Ok("12+34*5".to_string())
+        );
+        assert_eq!(
+            term("23+=5").to_infix_string_with(&EvaluationRules {
+                equations: true,
+                ..EvaluationRules::default()
+            }),
+            Ok("2+3=5".to_string())
+        );
+    }
+
+    #[test]
+    fn terms_parse_from_strings() {
+        assert_eq!("12+".parse::<Term>(), Ok(term("12+")));
+        assert_eq!(
+            "12a".parse::<Term>(),
+            Err(TermEvalError::InvalidCharacter('a'))
+        );
+    }
+
+    #[test]
+    fn expressions_evaluate_in_the_notation_of_the_rules() {
+        let infix = EvaluationRules {
+            notation: Notation::Infix,
+            ..EvaluationRules::default()
+        };
+
+        assert_eq!(eval_expression("12+3*"), Ok(9));
+        assert_eq!(eval_expression_with("12+3", &infix), Ok(15));
+        assert_eq!(eval_expression_with("2+3*4", &infix), Ok(14));
+        assert!(matches!(
+            eval_expression_with("12+", &infix),
+            Err(TermEvalError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn adjacent_operands_multiply_implicitly() {
+        let implicit = EvaluationRules {
+            notation: Notation::Infix,
+            implicit_multiplication: true,
+            ..EvaluationRules::default()
+        };
+
+        // the digits fold first, so 23 is one operand
+        assert_eq!(term("23").evaluate_with(&implicit), Ok(23));
+        assert_eq!(term("2(3)").evaluate_with(&implicit), Ok(6));
+        assert_eq!(term("2(3+4)").evaluate_with(&implicit), Ok(14));
+        assert_eq!(term("(1+2)(3+4)5").evaluate_with(&implicit), Ok(105));
+        assert_eq!(term("1+2(3)").evaluate_with(&implicit), Ok(7));
+        assert!(term("2(3)").evaluate_infix().is_err());
+    }
+}